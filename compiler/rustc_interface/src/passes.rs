@@ -808,15 +808,6 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
     sess.time("layout_testing", || layout_test::test_layout(tcx));
     sess.time("abi_testing", || abi_test::test_abi(tcx));
 
-    /*
-    if env::var_os("RUSTC_BOOTSTRAP").is_none() && env::var_os("UAF").is_some() {
-        sess.time("safedrop", || {
-	        println!("Starting safedrop as a compiler pass...");
-            /* Call safedrop_query() defined in compiler/rustc_mir_transform/src/lib.rs */
-            tcx.hir().par_body_owners(|def_id| tcx.ensure().query_safedrop(def_id));
-        });
-    }
-    */
     // Avoid overwhelming user with errors if borrow checking failed.
     // I'm not sure how helpful this is, to be honest, but it avoids a
     // lot of annoying errors in the ui tests (basically,
@@ -826,6 +817,32 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
         return Err(reported);
     }
 
+    // SafeDrop is opt-in. Crates built by bootstrap are marked with
+    // `-Z force-unstable-if-unmarked`, so skip those even if `-Z safedrop`
+    // leaks into their flags through `RUSTFLAGS`. Like the lints, the checks
+    // need MIR that has been built without errors.
+    if sess.opts.unstable_opts.safedrop.is_some()
+        && !sess.opts.unstable_opts.force_unstable_if_unmarked
+    {
+        sess.time("safedrop", || {
+            // Report an unreadable spec file even if no call is looked up in it.
+            tcx.ensure().safedrop_specs(());
+            let findings = if sess.opts.unstable_opts.safedrop_instances {
+                rustc_mir_transform::safedrop::instances::check_instances(tcx)
+            } else {
+                tcx.hir().par_body_owners(|def_id| tcx.ensure().query_safedrop(def_id));
+                tcx.hir()
+                    .body_owners()
+                    .flat_map(|def_id| tcx.query_safedrop(def_id).iter().cloned())
+                    .collect()
+            };
+            rustc_mir_transform::safedrop::report::write_report(tcx, findings);
+        });
+    }
+    if sess.opts.unstable_opts.send_sync && !sess.opts.unstable_opts.force_unstable_if_unmarked {
+        sess.time("send_sync", || rustc_mir_transform::send_sync::check_send_sync(tcx));
+    }
+
     sess.time("misc_checking_3", || {
         parallel!(
             {
@@ -19,6 +19,9 @@
 use rustc_session::config::{DumpMonoStatsFormat, MirSpanview};
 use rustc_session::config::{ErrorOutputType, ExternLocation, LocationDetail, Options, Strip};
 use rustc_session::config::{InstrumentCoverage, Passes};
+use rustc_session::config::{
+    SafeDropCheckers, SafeDropMirPhase, SafeDropMode, SafeDropReportFormat,
+};
 use rustc_session::lint::Level;
 use rustc_session::search_paths::SearchPath;
 use rustc_session::utils::{CanonicalizedPath, NativeLib, NativeLibKind};
@@ -723,6 +726,8 @@ macro_rules! untracked {
     untracked!(proc_macro_execution_strategy, ProcMacroExecutionStrategy::CrossThread);
     untracked!(profile_closures, true);
     untracked!(query_dep_graph, true);
+    untracked!(safedrop_report, Some("safedrop.json".into()));
+    untracked!(safedrop_report_format, SafeDropReportFormat::Sarif);
     untracked!(self_profile, SwitchWithOptPath::Enabled(None));
     untracked!(self_profile_events, Some(vec![String::new()]));
     untracked!(span_debug, true);
@@ -825,6 +830,13 @@ macro_rules! tracked {
     tracked!(relro_level, Some(RelroLevel::Full));
     tracked!(remap_cwd_prefix, Some(PathBuf::from("abc")));
     tracked!(report_delayed_bugs, true);
+    tracked!(safedrop, Some(SafeDropCheckers::all()));
+    tracked!(safedrop_instances, true);
+    tracked!(safedrop_loop_bound, 4);
+    tracked!(safedrop_mir_phase, SafeDropMirPhase::Cleanup);
+    tracked!(safedrop_mode, SafeDropMode::Dataflow);
+    tracked!(safedrop_spec, Some(PathBuf::from("safedrop.toml")));
+    tracked!(safedrop_traces, true);
     tracked!(sanitizer, SanitizerSet::ADDRESS);
     tracked!(sanitizer_cfi_canonical_jump_tables, None);
     tracked!(sanitizer_cfi_generalize_pointers, Some(true));
@@ -832,6 +844,7 @@ macro_rules! tracked {
     tracked!(sanitizer_memory_track_origins, 2);
     tracked!(sanitizer_recover, SanitizerSet::ADDRESS);
     tracked!(saturating_float_casts, Some(true));
+    tracked!(send_sync, true);
     tracked!(share_generics, Some(true));
     tracked!(show_span, Some(String::from("abc")));
     tracked!(simulate_remapped_rust_src_base, Some(PathBuf::from("/rustc/abc")));
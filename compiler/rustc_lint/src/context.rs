@@ -354,9 +354,11 @@ pub fn check_lint_name(
         registered_tools: &RegisteredTools,
     ) -> CheckLintNameResult<'_> {
         if let Some(tool_name) = tool_name {
-            // FIXME: rustc and rustdoc are considered tools for lints, but not for attributes.
+            // FIXME: rustc, rustdoc and safedrop are considered tools for lints, but not for
+            // attributes.
             if tool_name != sym::rustc
                 && tool_name != sym::rustdoc
+                && tool_name != sym::safedrop
                 && !registered_tools.contains(&Ident::with_dummy_span(tool_name))
             {
                 return CheckLintNameResult::NoTool;
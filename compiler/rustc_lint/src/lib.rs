@@ -93,7 +93,8 @@
 use rustc_middle::query::Providers;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::lint::builtin::{
-    BARE_TRAIT_OBJECTS, ELIDED_LIFETIMES_IN_PATHS, EXPLICIT_OUTLIVES_REQUIREMENTS,
+    BARE_TRAIT_OBJECTS, DANGLING_POINTER, DOUBLE_FREE, ELIDED_LIFETIMES_IN_PATHS,
+    EXPLICIT_OUTLIVES_REQUIREMENTS, MEMORY_LEAK, PANIC_SAFETY, UNINIT_MEMORY, USE_AFTER_FREE,
 };
 
 use array_into_iter::ArrayIntoIter;
@@ -267,6 +268,16 @@ macro_rules! add_lint_group {
     store.register_lints(&BuiltinCombinedModuleLateLintPass::get_lints());
     store.register_lints(&foreign_modules::get_lints());
 
+    add_lint_group!(
+        "safedrop::all",
+        USE_AFTER_FREE,
+        DOUBLE_FREE,
+        DANGLING_POINTER,
+        MEMORY_LEAK,
+        PANIC_SAFETY,
+        UNINIT_MEMORY
+    );
+
     add_lint_group!(
         "nonstandard_style",
         NON_CAMEL_CASE_TYPES,
@@ -4,7 +4,7 @@
 //! compiler code, rather than using their own custom pass. Those
 //! lints are all available in `rustc_lint::builtin`.
 
-use crate::{declare_lint, declare_lint_pass, FutureIncompatibilityReason};
+use crate::{declare_lint, declare_lint_pass, declare_tool_lint, FutureIncompatibilityReason};
 use rustc_span::edition::Edition;
 use rustc_span::symbol::sym;
 
@@ -3386,6 +3386,303 @@
     "name introduced by a private item shadows a name introduced by a public glob re-export",
 }
 
+declare_tool_lint! {
+    /// The `safedrop::use_after_free` lint detects a value, or a pointer
+    /// aliasing it, being used after the value has been dropped.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs -Z safedrop)
+    /// fn main() {
+    ///     let p;
+    ///     {
+    ///         let b = Box::new(1);
+    ///         p = &*b as *const i32;
+    ///     }
+    ///     let _x = unsafe { *p };
+    /// }
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// warning: use of a value after it has been dropped
+    ///  --> src/main.rs:7:23
+    ///   |
+    /// 6 |     }
+    ///   |     - value dropped here
+    /// 7 |     let _x = unsafe { *p };
+    ///   |                       ^^ value used here after being dropped
+    ///   |
+    ///   = note: `#[warn(safedrop::use_after_free)]` on by default
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// The memory of a dropped value may already have been freed or reused,
+    /// so reading or writing it through a pointer that outlived the value is
+    /// undefined behavior.
+    pub safedrop::USE_AFTER_FREE,
+    Warn,
+    "use of a value after it has been dropped"
+}
+
+declare_tool_lint! {
+    /// The `safedrop::double_free` lint detects a value being dropped again
+    /// through an alias after it has already been dropped.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs -Z safedrop)
+    /// fn main() {
+    ///     let p = Box::into_raw(Box::new(1));
+    ///     {
+    ///         let _a = unsafe { Box::from_raw(p) };
+    ///     }
+    ///     let _b = unsafe { Box::from_raw(p) };
+    /// }
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// warning: value dropped more than once
+    ///  --> src/main.rs:7:1
+    ///   |
+    /// 5 |     }
+    ///   |     - value first dropped here
+    /// 6 |     let _b = unsafe { Box::from_raw(p) };
+    /// 7 | }
+    ///   | ^ value dropped here again
+    ///   |
+    ///   = note: `#[warn(safedrop::double_free)]` on by default
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// Each owner of a value drops it, so two owners of the same allocation,
+    /// e.g. two boxes rebuilt from one raw pointer, free it twice, which is
+    /// undefined behavior. Give up one of the owners, e.g. with
+    /// `Box::into_raw` or `mem::forget`, before the other one is dropped.
+    pub safedrop::DOUBLE_FREE,
+    Warn,
+    "value dropped more than once"
+}
+
+declare_tool_lint! {
+    /// The `safedrop::dangling_pointer` lint detects a function returning, or
+    /// leaving behind in one of its arguments, a pointer to a dropped value.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs -Z safedrop)
+    /// fn dangling() -> *const i32 {
+    ///     let b = Box::new(1);
+    ///     let p = &*b as *const i32;
+    ///     drop(b);
+    ///     p
+    /// }
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// warning: pointer to a dropped value escapes this function
+    ///  --> src/lib.rs:1:1
+    ///   |
+    /// 1 | fn dangling() -> *const i32 {
+    ///   | ^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    /// ...
+    /// 4 |     drop(b);
+    ///   |     ------- value dropped here
+    ///   |
+    ///   = note: `#[warn(safedrop::dangling_pointer)]` on by default
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// The caller cannot tell that the pointer it gets back points to freed
+    /// memory, so any use of it is a use after free. Return the owner of the
+    /// value instead, or keep it alive for as long as the pointer is used.
+    pub safedrop::DANGLING_POINTER,
+    Warn,
+    "pointer to a dropped value escapes the function"
+}
+
+declare_tool_lint! {
+    /// The `safedrop::memory_leak` lint detects a value whose ownership is
+    /// given up, e.g., by `Box::into_raw` or `mem::forget`, and that is not
+    /// freed, returned or stored behind a pointer argument before the
+    /// function returns.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs -Z safedrop)
+    /// fn main() {
+    ///     let b = Box::new(1);
+    ///     let _p = Box::into_raw(b);
+    /// }
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// warning: value is never freed
+    ///  --> src/main.rs:3:14
+    ///   |
+    /// 3 |     let _p = Box::into_raw(b);
+    ///   |              ^^^^^^^^^^^^^^^^ ownership of the value given up here
+    ///   |
+    ///   = note: it is not freed, returned, or stored behind a pointer argument before the function returns
+    ///   = note: `#[warn(safedrop::memory_leak)]` on by default
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// Leaking is safe, but a value whose ownership is given up and that
+    /// nothing can reach any more is never freed. If the leak is intended,
+    /// e.g. for a value that lives until the program exits, use `Box::leak`
+    /// and keep the reference it returns.
+    pub safedrop::MEMORY_LEAK,
+    Warn,
+    "value leaked without being freed"
+}
+
+declare_tool_lint! {
+    /// The `safedrop::panic_safety` lint detects ownership duplicated bit-wise,
+    /// e.g., by `ptr::read` or `Vec::set_len`, that is still duplicated when a
+    /// call which may panic is made.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs -Z safedrop)
+    /// fn map_first<T>(v: &mut Vec<T>, f: fn(T) -> T) {
+    ///     let p = v.as_mut_ptr();
+    ///     unsafe {
+    ///         let x = std::ptr::read(p);
+    ///         let y = f(x);
+    ///         std::ptr::write(p, y);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// warning: value may be dropped twice if a call panics
+    ///  --> src/lib.rs:4:17
+    ///   |
+    /// 4 |         let x = std::ptr::read(p);
+    ///   |                 ^^^^^^^^^^^^^^^^^ ownership of the value duplicated here
+    /// 5 |         let y = f(x);
+    ///   |                 ---- both owners are dropped if this call panics
+    ///   |
+    ///   = note: `#[warn(safedrop::panic_safety)]` on by default
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// If the call unwinds, both the duplicate and the original owner, here
+    /// the vector, are dropped, which frees the value twice. Give up one of
+    /// the owners before the call, e.g. by setting the length of the vector
+    /// to zero, and restore it afterwards.
+    pub safedrop::PANIC_SAFETY,
+    Warn,
+    "value may be dropped twice if a call panics"
+}
+
+declare_tool_lint! {
+    /// The `safedrop::uninit_memory` lint detects memory being read or
+    /// dropped before it is initialized, e.g., a `MaybeUninit` on which
+    /// `assume_init` is called before it is written, or the memory returned
+    /// by `alloc`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs -Z safedrop)
+    /// use std::mem::MaybeUninit;
+    ///
+    /// fn main() {
+    ///     let s = MaybeUninit::<String>::uninit();
+    ///     let _s = unsafe { s.assume_init() };
+    /// }
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// warning: memory is read before it is initialized
+    ///  --> src/main.rs:5:23
+    ///   |
+    /// 4 |     let s = MaybeUninit::<String>::uninit();
+    ///   |             ------------------------------- memory created here without being initialized
+    /// 5 |     let _s = unsafe { s.assume_init() };
+    ///   |                       ^^^^^^^^^^^^^^^ read here
+    ///   |
+    ///   = note: `#[warn(safedrop::uninit_memory)]` on by default
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// Uninitialized memory does not hold a valid value of its type, so
+    /// reading it, or dropping it as if it did, is undefined behavior.
+    /// Write the memory first, e.g. with `MaybeUninit::write`.
+    pub safedrop::UNINIT_MEMORY,
+    Warn,
+    "use of memory before it is initialized"
+}
+
+declare_tool_lint! {
+    /// The `rap::unsound_send_sync` lint detects an `unsafe impl Send` or
+    /// `unsafe impl Sync` for a generic type that does not require a
+    /// `T: Send` or `T: Sync` bound its fields or its `&self` API need,
+    /// e.g., `Sync` for a type with a `fn get(&self) -> &T` method without
+    /// `T: Sync`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs -Z send-sync)
+    /// use std::cell::UnsafeCell;
+    ///
+    /// pub struct Cell<T> {
+    ///     value: UnsafeCell<T>,
+    /// }
+    ///
+    /// impl<T> Cell<T> {
+    ///     pub fn get(&self) -> &T {
+    ///         unsafe { &*self.value.get() }
+    ///     }
+    /// }
+    ///
+    /// unsafe impl<T: Send> Sync for Cell<T> {}
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// warning: `Sync` is implemented for `Cell<T>` without requiring `T: Sync`
+    ///   --> src/lib.rs:13:1
+    ///    |
+    /// 8  |     pub fn get(&self) -> &T {
+    ///    |         ------------------- `&T` is given out through `&self` here
+    /// ...
+    /// 13 | unsafe impl<T: Send> Sync for Cell<T> {}
+    ///    | ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    ///    |
+    ///    = note: `#[warn(rap::unsound_send_sync)]` on by default
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// A `&Cell<T>` shared with another thread gives that thread a `&T`, which
+    /// is only sound if `T: Sync`, but the impl lets any `T: Send` through,
+    /// e.g. a `std::cell::Cell<i32>`, which is `Send` but not `Sync`. The
+    /// check is opt-in, with `-Z send-sync`.
+    pub rap::UNSOUND_SEND_SYNC,
+    Warn,
+    "`Send` or `Sync` implementation without the bounds on type parameters it needs"
+}
+
 declare_lint_pass! {
     /// Does nothing as a lint pass, but registers some `Lint`s
     /// that are used by other parts of the compiler.
@@ -3409,11 +3706,13 @@
         CONST_EVALUATABLE_UNCHECKED,
         CONST_ITEM_MUTATION,
         CONST_PATTERNS_WITHOUT_PARTIAL_EQ,
+        DANGLING_POINTER,
         DEAD_CODE,
         DEPRECATED,
         DEPRECATED_CFG_ATTR_CRATE_TYPE_NAME,
         DEPRECATED_IN_FUTURE,
         DEPRECATED_WHERE_CLAUSE_LOCATION,
+        DOUBLE_FREE,
         DUPLICATE_MACRO_ATTRIBUTES,
         ELIDED_LIFETIMES_IN_ASSOCIATED_CONSTANT,
         ELIDED_LIFETIMES_IN_PATHS,
@@ -3442,6 +3741,7 @@
         LOSSY_PROVENANCE_CASTS,
         MACRO_EXPANDED_MACRO_EXPORTS_ACCESSED_BY_ABSOLUTE_PATHS,
         MACRO_USE_EXTERN_CRATE,
+        MEMORY_LEAK,
         META_VARIABLE_MISUSE,
         MISSING_ABI,
         MISSING_FRAGMENT_SPECIFIER,
@@ -3451,6 +3751,7 @@
         NONTRIVIAL_STRUCTURAL_MATCH,
         ORDER_DEPENDENT_TRAIT_OBJECTS,
         OVERLAPPING_RANGE_ENDPOINTS,
+        PANIC_SAFETY,
         PATTERNS_IN_FNS_WITHOUT_BODY,
         POINTER_STRUCTURAL_MATCH,
         PRIVATE_BOUNDS,
@@ -3480,6 +3781,7 @@
         UNDEFINED_NAKED_FUNCTION_ABI,
         UNFULFILLED_LINT_EXPECTATIONS,
         UNINHABITED_STATIC,
+        UNINIT_MEMORY,
         UNKNOWN_CRATE_TYPES,
         UNKNOWN_LINTS,
         UNKNOWN_OR_MALFORMED_DIAGNOSTIC_ATTRIBUTES,
@@ -3488,6 +3790,7 @@
         UNREACHABLE_CODE,
         UNREACHABLE_PATTERNS,
         UNSAFE_OP_IN_UNSAFE_FN,
+        UNSOUND_SEND_SYNC,
         UNSTABLE_NAME_COLLISIONS,
         UNSTABLE_SYNTAX_PRE_EXPANSION,
         UNSUPPORTED_CALLING_CONVENTIONS,
@@ -3507,6 +3810,7 @@
         UNUSED_TUPLE_STRUCT_FIELDS,
         UNUSED_UNSAFE,
         UNUSED_VARIABLES,
+        USE_AFTER_FREE,
         USELESS_DEPRECATED,
         WARNINGS,
         WHERE_CLAUSES_OBJECT_SAFETY,
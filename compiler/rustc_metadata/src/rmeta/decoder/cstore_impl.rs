@@ -223,6 +223,7 @@ fn into_args(self) -> (DefId, SimplifiedType) {
     closure_saved_names_of_captured_variables => { table }
     mir_generator_witnesses => { table }
     promoted_mir => { table }
+    safedrop_ret_alias => { table }
     def_span => { table }
     def_ident_span => { table }
     lookup_stability => { table }
@@ -1653,6 +1653,23 @@ fn encode_mir(&mut self) {
             self.tables.unused_generic_params.set(def_id.local_def_index, unused);
         }
 
+        // Encode the SafeDrop summaries of the functions other crates can call, including the
+        // ones whose MIR is not encoded, so that downstream crates do not have to guess the
+        // effect of calling them.
+        if tcx.sess.opts.unstable_opts.safedrop.is_some()
+            && !tcx.sess.opts.unstable_opts.force_unstable_if_unmarked
+        {
+            for &local_def_id in tcx.mir_keys(()) {
+                if let DefKind::AssocFn | DefKind::Fn | DefKind::Closure = tcx.def_kind(local_def_id)
+                    && (reachable_set.contains(&local_def_id)
+                        || should_encode_mir(tcx, reachable_set, local_def_id).1)
+                    && let Some(summary) = tcx.safedrop_ret_alias(local_def_id)
+                {
+                    record!(self.tables.safedrop_ret_alias[local_def_id.to_def_id()] <- summary);
+                }
+            }
+        }
+
         // Encode all the deduced parameter attributes for everything that has MIR, even for items
         // that can't be inlined. But don't if we aren't optimizing in non-incremental mode, to
         // save the query traffic.
@@ -55,6 +55,7 @@
 pub mod patch;
 pub mod pretty;
 mod query;
+pub mod safedrop;
 pub mod spanview;
 mod statement;
 mod syntax;
@@ -0,0 +1,243 @@
+//! Function summaries computed by the SafeDrop analysis in `rustc_mir_transform`.
+//!
+//! They are stored in crate metadata so that downstream crates can reuse the summaries of
+//! their dependencies instead of guessing the effect of calls without available MIR. The
+//! findings of the analysis and the function specs it is given are kept here too, as the
+//! results of `query_safedrop` and `safedrop_specs`.
+
+use rustc_data_structures::fx::{FxIndexMap, FxIndexSet};
+use rustc_hir::def_id::DefId;
+use rustc_session::lint::builtin::{
+    DANGLING_POINTER, DOUBLE_FREE, MEMORY_LEAK, PANIC_SAFETY, UNINIT_MEMORY, USE_AFTER_FREE,
+};
+use rustc_session::lint::Lint;
+use rustc_span::Span;
+
+use crate::ty::TyCtxt;
+
+/// An alias relationship between two arguments (or an argument and the return value) of a
+/// function, established by the time it returns.
+///
+/// Index `0` is the return value and `1..=arg_size` are the arguments. `left` and `right`
+/// are the field paths below those locals.
+#[derive(Clone, Debug, PartialEq, Eq, TyEncodable, TyDecodable, HashStable)]
+pub struct RetAlias {
+    pub left_index: usize,
+    pub left: Vec<usize>,
+    pub left_may_drop: bool,
+    pub left_need_drop: bool,
+    pub right_index: usize,
+    pub right: Vec<usize>,
+    pub right_may_drop: bool,
+    pub right_need_drop: bool,
+    pub atype: usize,
+}
+
+impl RetAlias {
+    pub fn new(
+        atype: usize,
+        left_index: usize,
+        left_may_drop: bool,
+        left_need_drop: bool,
+        right_index: usize,
+        right_may_drop: bool,
+        right_need_drop: bool,
+    ) -> RetAlias {
+        RetAlias {
+            left_index,
+            left: Vec::new(),
+            left_may_drop,
+            left_need_drop,
+            right_index,
+            right: Vec::new(),
+            right_may_drop,
+            right_need_drop,
+            atype,
+        }
+    }
+
+    /// Only aliases between values that may be dropped are relevant to SafeDrop.
+    pub fn valuable(&self) -> bool {
+        self.left_may_drop && self.right_may_drop
+    }
+}
+
+/// The effect of a function on its arguments and return value: the aliases it creates
+/// (one entry per distinct return path) and the pointer arguments it drops.
+#[derive(Clone, Debug, TyEncodable, TyDecodable, HashStable)]
+pub struct FnRetAlias {
+    pub arg_size: usize,
+    pub alias_vec: Vec<RetAlias>,
+    pub dead: FxIndexSet<usize>,
+}
+
+impl FnRetAlias {
+    pub fn new(arg_size: usize) -> FnRetAlias {
+        FnRetAlias { arg_size, alias_vec: Vec::new(), dead: FxIndexSet::default() }
+    }
+
+    /// Adds the aliases and drops of `other` to `self`, returning whether anything was new.
+    pub fn join(&mut self, other: &FnRetAlias) -> bool {
+        let mut changed = false;
+        for alias in &other.alias_vec {
+            if !self.alias_vec.contains(alias) {
+                self.alias_vec.push(alias.clone());
+                changed = true;
+            }
+        }
+        for &dead in &other.dead {
+            changed |= self.dead.insert(dead);
+        }
+        changed
+    }
+}
+
+/// The local functions and the local functions they call, grouped into the strongly connected
+/// components of the call graph. The functions of a component are summarized together, so
+/// that mutually recursive functions get sound summaries. Upstream functions are not part of
+/// the graph: their summaries, if any, are stored in the metadata of their crate.
+#[derive(Debug, Default, HashStable)]
+pub struct SafeDropCallGraph {
+    /// The component of each function.
+    pub scc_of: FxIndexMap<DefId, usize>,
+    /// The functions of each component.
+    pub members: Vec<Vec<DefId>>,
+    /// Whether a component has a cycle, i.e. several functions or one that calls itself.
+    pub recursive: Vec<bool>,
+}
+
+impl SafeDropCallGraph {
+    /// The component of `def_id`, if it is part of the graph.
+    pub fn scc(&self, def_id: DefId) -> Option<usize> {
+        self.scc_of.get(&def_id).copied()
+    }
+}
+
+/// Whether the caller of a function declared in `-Z safedrop-spec` has to free its result.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, HashStable)]
+pub enum Ownership {
+    #[default]
+    Owned,
+    Borrowed,
+}
+
+/// The effects of a library or FFI function, as declared in `-Z safedrop-spec`. Arguments are
+/// numbered from 0.
+#[derive(Debug, Clone, HashStable)]
+pub struct FnSpec {
+    pub name: String,
+    /// The arguments whose targets the function frees.
+    pub frees: Vec<usize>,
+    /// The arguments that the returned value points into.
+    pub aliases_return: Vec<usize>,
+    pub returns: Ownership,
+}
+
+/// The kinds of bugs SafeDrop reports, one per lint of its `safedrop` tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, HashStable)]
+pub enum BugKind {
+    UseAfterFree,
+    DoubleFree,
+    DanglingPointer,
+    MemoryLeak,
+    PanicSafety,
+    UninitMemory,
+}
+
+impl BugKind {
+    pub const ALL: [BugKind; 6] = [
+        BugKind::UseAfterFree,
+        BugKind::DoubleFree,
+        BugKind::DanglingPointer,
+        BugKind::MemoryLeak,
+        BugKind::PanicSafety,
+        BugKind::UninitMemory,
+    ];
+
+    pub fn lint(self) -> &'static Lint {
+        match self {
+            BugKind::UseAfterFree => USE_AFTER_FREE,
+            BugKind::DoubleFree => DOUBLE_FREE,
+            BugKind::DanglingPointer => DANGLING_POINTER,
+            BugKind::MemoryLeak => MEMORY_LEAK,
+            BugKind::PanicSafety => PANIC_SAFETY,
+            BugKind::UninitMemory => UNINIT_MEMORY,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BugKind::UseAfterFree => "use_after_free",
+            BugKind::DoubleFree => "double_free",
+            BugKind::DanglingPointer => "dangling_pointer",
+            BugKind::MemoryLeak => "memory_leak",
+            BugKind::PanicSafety => "panic_safety",
+            BugKind::UninitMemory => "uninit_memory",
+        }
+    }
+
+    /// The message of the secondary site of a finding, kept in `drop_site`.
+    pub fn drop_site_message(self) -> &'static str {
+        match self {
+            BugKind::PanicSafety => "both owners are dropped if this call panics",
+            BugKind::UninitMemory => "memory created here without being initialized",
+            _ => "value dropped here",
+        }
+    }
+
+    /// The rule id is the lint name, so that it can be matched against `#[allow(..)]` attributes.
+    pub fn rule_id(self) -> String {
+        format!("safedrop::{}", self.name())
+    }
+}
+
+/// A file/line/column range; lines and columns are 1-based.
+#[derive(Debug, Clone, HashStable)]
+pub struct SpanRange {
+    pub file: String,
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl SpanRange {
+    pub fn new(tcx: TyCtxt<'_>, span: Span) -> SpanRange {
+        let (file, start_line, start_column, end_line, end_column) =
+            tcx.sess.source_map().span_to_location_info(span);
+        let file = match file {
+            Some(file) => file.name.prefer_local().to_string(),
+            None => "<unknown>".to_string(),
+        };
+        SpanRange { file, start_line, start_column, end_line, end_column }
+    }
+}
+
+/// An assignment or a call that aliased the value involved in a finding.
+#[derive(Debug, Clone, HashStable)]
+pub struct AliasRecord {
+    pub span: SpanRange,
+    pub callee: Option<String>,
+}
+
+/// The witness of a finding: the basic blocks of the path to it, and the sites that aliased
+/// the value.
+#[derive(Debug, Clone, HashStable)]
+pub struct TraceRecord {
+    pub path: Vec<String>,
+    pub aliases: Vec<AliasRecord>,
+}
+
+/// One reported bug, as written to the `-Z safedrop-report` file. The findings of a body are
+/// the result of its `query_safedrop`, so that the report also has the findings of bodies
+/// whose query is not re-run by an incremental build.
+#[derive(Debug, Clone, HashStable)]
+pub struct Finding {
+    pub kind: BugKind,
+    pub level: &'static str,
+    pub function: String,
+    pub span: SpanRange,
+    pub unwind: bool,
+    pub drop_site: Option<SpanRange>,
+    pub trace: TraceRecord,
+}
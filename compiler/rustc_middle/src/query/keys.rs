@@ -521,6 +521,15 @@ fn default_span(&self, _tcx: TyCtxt<'_>) -> Span {
     }
 }
 
+/// Indices into the result of a `()` query, e.g. the components of the SafeDrop call graph.
+impl Key for usize {
+    type CacheSelector = DefaultCacheSelector<Self>;
+
+    fn default_span(&self, _tcx: TyCtxt<'_>) -> Span {
+        DUMMY_SP
+    }
+}
+
 impl<'tcx> Key for (DefId, Ty<'tcx>, GenericArgsRef<'tcx>, ty::ParamEnv<'tcx>) {
     type CacheSelector = DefaultCacheSelector<Self>;
 
@@ -108,8 +108,70 @@
 // as they will raise an fatal error on query cycles instead.
 rustc_queries! {
 
-    query query_safedrop(_: DefId) -> () {
-        desc { "check safedrop bugs in rustc mir" }
+    /// Runs the SafeDrop memory-safety checks enabled by `-Z safedrop` on the MIR of `key`,
+    /// reporting their lints and returning the findings for `-Z safedrop-report`.
+    query query_safedrop(key: DefId) -> &'tcx Vec<mir::safedrop::Finding> {
+        arena_cache
+        desc { |tcx| "running SafeDrop on `{}`", tcx.def_path_str(key) }
+    }
+
+    /// The MIR of `key` that SafeDrop analyses, of the phase selected by `-Z safedrop-mir-phase`.
+    /// Generators are analysed before the state transform, which moves their locals into the
+    /// fields of the generator.
+    query safedrop_mir(key: LocalDefId) -> &'tcx mir::Body<'tcx> {
+        desc { |tcx| "building the SafeDrop MIR of `{}`", tcx.def_path_str(key) }
+    }
+
+    /// The instances of the local functions that code generation uses, by function, which
+    /// `-Z safedrop-instances` analyses. Empty in builds that do not generate code.
+    query safedrop_mono_instances(_: ()) -> &'tcx FxIndexMap<LocalDefId, Vec<ty::Instance<'tcx>>> {
+        arena_cache
+        desc { "collecting the instances SafeDrop analyses" }
+    }
+
+    /// Runs SafeDrop on the instances of `key` in `safedrop_mono_instances`, reporting the
+    /// bugs of all of them at once and returning their findings.
+    query safedrop_check_instances(key: LocalDefId) -> &'tcx Vec<mir::safedrop::Finding> {
+        arena_cache
+        desc { |tcx| "running SafeDrop on the instances of `{}`", tcx.def_path_str(key) }
+    }
+
+    /// The local functions that the body of `key` calls, with trait methods resolved and
+    /// shims looked through, which SafeDrop summarizes from their MIR.
+    query safedrop_callees(key: LocalDefId) -> &'tcx [LocalDefId] {
+        desc { |tcx| "collecting the SafeDrop callees of `{}`", tcx.def_path_str(key) }
+    }
+
+    /// The local functions, grouped into the strongly connected components of the call graph
+    /// that SafeDrop summarizes together.
+    query safedrop_call_graph(_: ()) -> &'tcx mir::safedrop::SafeDropCallGraph {
+        arena_cache
+        desc { "building the SafeDrop call graph" }
+    }
+
+    /// Computes the SafeDrop summaries of all functions in the component `key` of the call
+    /// graph, iterating recursive components to a fixed point.
+    query safedrop_scc_summaries(key: usize) -> &'tcx FxIndexMap<DefId, mir::safedrop::FnRetAlias> {
+        arena_cache
+        desc { "computing the SafeDrop summaries of a component of the call graph" }
+    }
+
+    /// The effects of library and FFI functions declared in the file given by
+    /// `-Z safedrop-spec`, by the name they are looked up with. The file is read in every
+    /// session, so that the checks are re-run only if its contents change.
+    query safedrop_specs(_: ()) -> &'tcx FxIndexMap<String, mir::safedrop::FnSpec> {
+        arena_cache
+        eval_always
+        desc { "loading the SafeDrop function specs" }
+    }
+
+    /// The SafeDrop summary of `key` as stored in crate metadata. Summaries of local
+    /// functions are stored when SafeDrop is enabled; for upstream functions this is
+    /// `None` unless their crate was built that way.
+    query safedrop_ret_alias(key: DefId) -> &'tcx Option<mir::safedrop::FnRetAlias> {
+        arena_cache
+        desc { |tcx| "exporting the SafeDrop summary of `{}`", tcx.def_path_str(key) }
+        separate_provide_extern
     }
 
     query trigger_delay_span_bug(key: DefId) -> () {
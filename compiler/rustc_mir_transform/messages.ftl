@@ -42,6 +42,68 @@ mir_transform_requires_unsafe = {$details} is unsafe and requires unsafe {$op_in
     }
     .not_inherited = items do not inherit unsafety from separate enclosing items
 
+mir_transform_safedrop_alias_label = value aliased here
+
+mir_transform_safedrop_dangling_pointer = {$unwind ->
+    [true] pointer to a dropped value escapes this function when it unwinds
+    *[false] pointer to a dropped value escapes this function
+    }
+    .drop_label = value dropped here
+
+mir_transform_safedrop_double_free = value dropped more than once
+    .label = value dropped here again
+    .first_drop_label = value first dropped here
+
+mir_transform_safedrop_memory_leak = value is never freed
+    .label = ownership of the value given up here
+    .note = it is not freed, returned, or stored behind a pointer argument before the function returns
+
+mir_transform_safedrop_panic_safety = value may be dropped twice if a call panics
+    .label = ownership of the value duplicated here
+    .call_label = both owners are dropped if this call panics
+
+mir_transform_safedrop_path_note = path to the bug: {$path}
+
+mir_transform_safedrop_report_error =
+    failed to write SafeDrop report to `{$path}`: {$error}
+
+mir_transform_safedrop_spec_error =
+    failed to load SafeDrop specifications from `{$path}`: {$error}
+
+mir_transform_safedrop_summary_label = value aliased by the call to `{$callee}`
+
+mir_transform_safedrop_uninit_memory = {$is_drop ->
+    [true] memory is dropped before it is initialized
+    *[false] memory is read before it is initialized
+    }
+    .label = {$is_drop ->
+        [true] dropped here
+        *[false] read here
+    }
+    .uninit_label = memory created here without being initialized
+
+mir_transform_safedrop_unknown_attr = unknown SafeDrop attribute `rap::{$name}`
+    .help = the SafeDrop attributes are `rap::allow`, `rap::deallocator` and `rap::trusted`
+
+mir_transform_safedrop_unknown_checker = unknown SafeDrop checker `{$name}`
+    .help = the checkers are `double_free`, `use_after_free`, `dangling_pointer`, `memory_leak`, `panic_safety` and `uninit_memory`
+
+mir_transform_safedrop_use_after_free = use of a value after it has been dropped
+    .label = value used here after being dropped
+    .drop_label = value dropped here
+
+mir_transform_send_sync_missing_bound =
+    `{$trait_name}` is implemented for `{$self_ty}` without requiring `{$ty}: {$bound}`
+    .use_label = {$usage ->
+        [field_owned] `{$ty}` is owned by this field
+        [field_shared] `&{$ty}` is held by this field
+        [field_mut] `&mut {$ty}` is held by this field
+        [field_pub] `{$ty}` is reachable through this public field
+        [api_out] `{$ty}` is given out through `&self` here
+        [api_out_ref] `&{$ty}` is given out through `&self` here
+        *[api_in] `{$ty}` is taken in through `&self` here
+    }
+
 mir_transform_target_feature_call_label = call to function with `#[target_feature]`
 mir_transform_target_feature_call_note = can only be called if the required target features are available
 
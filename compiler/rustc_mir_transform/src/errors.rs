@@ -4,10 +4,10 @@
 };
 use rustc_macros::{Diagnostic, LintDiagnostic, Subdiagnostic};
 use rustc_middle::mir::{AssertKind, UnsafetyViolationDetails};
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::{Ty, TyCtxt};
 use rustc_session::lint::{self, Lint};
 use rustc_span::def_id::DefId;
-use rustc_span::Span;
+use rustc_span::{Span, Symbol};
 
 #[derive(LintDiagnostic)]
 pub(crate) enum ConstMutate {
@@ -278,3 +278,129 @@ pub(crate) struct MustNotSuspendReason {
     pub span: Span,
     pub reason: String,
 }
+
+#[derive(LintDiagnostic)]
+#[diag(mir_transform_safedrop_use_after_free)]
+pub(crate) struct SafeDropUseAfterFree {
+    #[label]
+    pub span: Span,
+    #[label(mir_transform_drop_label)]
+    pub drop_span: Option<Span>,
+    #[subdiagnostic]
+    pub trace: Vec<SafeDropTrace>,
+}
+
+#[derive(LintDiagnostic)]
+#[diag(mir_transform_safedrop_double_free)]
+pub(crate) struct SafeDropDoubleFree {
+    #[label]
+    pub span: Span,
+    #[label(mir_transform_first_drop_label)]
+    pub drop_span: Option<Span>,
+    #[subdiagnostic]
+    pub trace: Vec<SafeDropTrace>,
+}
+
+#[derive(LintDiagnostic)]
+#[diag(mir_transform_safedrop_dangling_pointer)]
+pub(crate) struct SafeDropDanglingPointer {
+    #[label(mir_transform_drop_label)]
+    pub drop_span: Option<Span>,
+    pub unwind: bool,
+    #[subdiagnostic]
+    pub trace: Vec<SafeDropTrace>,
+}
+
+#[derive(LintDiagnostic)]
+#[diag(mir_transform_safedrop_memory_leak)]
+#[note]
+pub(crate) struct SafeDropMemoryLeak {
+    #[label]
+    pub span: Span,
+    #[subdiagnostic]
+    pub trace: Vec<SafeDropTrace>,
+}
+
+#[derive(LintDiagnostic)]
+#[diag(mir_transform_safedrop_panic_safety)]
+pub(crate) struct SafeDropPanicSafety {
+    #[label]
+    pub span: Span,
+    #[label(mir_transform_call_label)]
+    pub call_span: Option<Span>,
+    #[subdiagnostic]
+    pub trace: Vec<SafeDropTrace>,
+}
+
+#[derive(LintDiagnostic)]
+#[diag(mir_transform_safedrop_uninit_memory)]
+pub(crate) struct SafeDropUninitMemory {
+    #[label]
+    pub span: Span,
+    pub is_drop: bool,
+    #[label(mir_transform_uninit_label)]
+    pub uninit_span: Option<Span>,
+    #[subdiagnostic]
+    pub trace: Vec<SafeDropTrace>,
+}
+
+#[derive(Subdiagnostic)]
+pub(crate) enum SafeDropTrace {
+    #[label(mir_transform_safedrop_alias_label)]
+    Alias {
+        #[primary_span]
+        span: Span,
+    },
+    #[label(mir_transform_safedrop_summary_label)]
+    Summary {
+        #[primary_span]
+        span: Span,
+        callee: String,
+    },
+    #[note(mir_transform_safedrop_path_note)]
+    Path { path: String },
+}
+
+#[derive(Diagnostic)]
+#[diag(mir_transform_safedrop_spec_error)]
+pub(crate) struct SafeDropSpecError {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(mir_transform_safedrop_unknown_attr)]
+#[help]
+pub(crate) struct SafeDropUnknownAttr {
+    #[primary_span]
+    pub span: Span,
+    pub name: Symbol,
+}
+
+#[derive(Diagnostic)]
+#[diag(mir_transform_safedrop_unknown_checker)]
+#[help]
+pub(crate) struct SafeDropUnknownChecker {
+    #[primary_span]
+    pub span: Span,
+    pub name: Symbol,
+}
+
+#[derive(Diagnostic)]
+#[diag(mir_transform_safedrop_report_error)]
+pub(crate) struct SafeDropReportError {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(LintDiagnostic)]
+#[diag(mir_transform_send_sync_missing_bound)]
+pub(crate) struct SendSyncMissingBound<'tcx> {
+    pub trait_name: Symbol,
+    pub self_ty: Ty<'tcx>,
+    pub ty: Ty<'tcx>,
+    pub bound: Symbol,
+    pub usage: &'static str,
+    #[label(mir_transform_use_label)]
+    pub use_span: Span,
+}
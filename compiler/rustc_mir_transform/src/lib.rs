@@ -37,6 +37,7 @@
 };
 use rustc_middle::query::Providers;
 use rustc_middle::ty::{self, TyCtxt, TypeVisitableExt};
+use rustc_session::config::SafeDropMirPhase;
 use rustc_span::sym;
 use rustc_trait_selection::traits;
 
@@ -117,8 +118,8 @@
 use rustc_fluent_macro::fluent_messages;
 
 pub mod safedrop;
+pub mod send_sync;
 use safedrop::safedrop::*;
-use safedrop::graph::*;
 use safedrop::log::*;
 use log::Log;
 
@@ -131,6 +132,14 @@ pub fn provide(providers: &mut Providers) {
     shim::provide(providers);
     *providers = Providers {
         query_safedrop,
+        safedrop_mir,
+        safedrop_call_graph: safedrop::summary::safedrop_call_graph,
+        safedrop_scc_summaries: safedrop::summary::safedrop_scc_summaries,
+        safedrop_callees: safedrop::summary::safedrop_callees,
+        safedrop_mono_instances: safedrop::instances::safedrop_mono_instances,
+        safedrop_check_instances: safedrop::instances::safedrop_check_instances,
+        safedrop_ret_alias: safedrop::summary::safedrop_ret_alias,
+        safedrop_specs: safedrop::spec::safedrop_specs,
         mir_keys,
         mir_const,
         mir_const_qualif,
@@ -149,25 +158,78 @@ pub fn provide(providers: &mut Providers) {
     };
 }
 
-fn query_safedrop<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId) -> () {
+fn query_safedrop<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId) -> Vec<safedrop::report::Finding> {
     /* filter const mir */
     if let Some(_other) = tcx.hir().body_const_context(def_id.expect_local()) {
-        return;
+        return Vec::new();
     }
     if tcx.is_mir_available(def_id) {
-        let body = tcx.optimized_mir(def_id);
+        let body = tcx.safedrop_mir(def_id.expect_local());
         let mut func_map = FuncMap::new();
-        let mut safedrop_graph = SafeDropGraph::new(&body, tcx, def_id);
-        safedrop_graph.solve_scc();
-        safedrop_graph.check(0, tcx, &mut func_map);
-        if safedrop_graph.visit_times <= VISIT_LIMIT { 
-	         safedrop_graph.report_bugs(); 
-	    } else { 
-	        rap_info!("Over visited: {:?}", def_id); 
-	    }
+        let safedrop_graph = analyze_body(tcx, &body, def_id, &mut func_map);
+        safedrop::dump::dump_safedrop_graph(tcx, &body, &safedrop_graph);
+        if safedrop_graph.visit_times <= VISIT_LIMIT {
+            return safedrop_graph.report_bugs();
+        } else {
+            rap_info!("Over visited: {:?}", def_id);
+        }
+    }
+    Vec::new()
+}
+
+/// The MIR SafeDrop analyses, of the phase selected by `-Z safedrop-mir-phase`. Generators are
+/// always lowered like in `run_analysis_to_runtime_passes` but without the state transform, so
+/// that their locals are still locals and the `Yield` terminators are kept.
+fn safedrop_mir(tcx: TyCtxt<'_>, def: LocalDefId) -> &Body<'_> {
+    if tcx.def_kind(def) == DefKind::Generator {
+        return safedrop_cleanup_mir(tcx, def);
+    }
+    match tcx.sess.opts.unstable_opts.safedrop_mir_phase {
+        SafeDropMirPhase::Optimized => tcx.optimized_mir(def),
+        // constructors have no MIR of their own before `optimized_mir`.
+        _ if tcx.is_constructor(def.to_def_id()) => tcx.optimized_mir(def),
+        SafeDropMirPhase::DropsElaborated => {
+            tcx.arena.alloc(tcx.mir_drops_elaborated_and_const_checked(def).borrow().clone())
+        }
+        SafeDropMirPhase::Cleanup => safedrop_cleanup_mir(tcx, def),
     }
 }
 
+/// Whether `safedrop_mir` of `def` is built from a MIR that the given phase steals, so it has
+/// to be computed before. `const fn`s are not checked themselves but summarized for their
+/// callers, so their MIR is kept too.
+fn safedrop_mir_reads(tcx: TyCtxt<'_>, def: LocalDefId, phase: SafeDropMirPhase) -> bool {
+    tcx.sess.opts.unstable_opts.safedrop.is_some()
+        && !matches!(
+            tcx.hir().body_const_context(def),
+            Some(hir::ConstContext::Const { .. } | hir::ConstContext::Static(_))
+        )
+        && (tcx.def_kind(def) == DefKind::Generator
+            || tcx.sess.opts.unstable_opts.safedrop_mir_phase == phase)
+}
+
+/// The borrow-checked MIR of `def`, lowered by the cleanup passes SafeDrop needs only.
+fn safedrop_cleanup_mir(tcx: TyCtxt<'_>, def: LocalDefId) -> &Body<'_> {
+    tcx.ensure_with_value().mir_borrowck(def);
+    let mut body = tcx.mir_promoted(def).0.borrow().clone();
+    run_analysis_cleanup_passes(tcx, &mut body);
+    // The body stays in the analysis phase, which is the only one with `Yield` terminators.
+    let passes: &[&dyn MirPass<'_>] = &[
+        &add_call_guards::CriticalCallEdges,
+        &reveal_all::RevealAll,
+        &add_subtyping_projections::Subtyper,
+        &elaborate_drops::ElaborateDrops,
+        &abort_unwinding_calls::AbortUnwindingCalls,
+        &add_moves_for_packed_drops::AddMovesForPackedDrops,
+        &elaborate_box_derefs::ElaborateBoxDerefs,
+        &lower_intrinsics::LowerIntrinsics,
+        &remove_place_mention::RemovePlaceMention,
+        &simplify::SimplifyCfg::ElaborateDrops,
+    ];
+    pm::run_passes_no_validate(tcx, &mut body, passes, None);
+    tcx.arena.alloc(body)
+}
+
 fn remap_mir_for_const_eval_select<'tcx>(
     tcx: TyCtxt<'tcx>,
     mut body: Body<'tcx>,
@@ -389,6 +451,11 @@ fn mir_drops_elaborated_and_const_checked(tcx: TyCtxt<'_>, def: LocalDefId) -> &
     if let DefKind::Generator = tcx.def_kind(def) {
         tcx.ensure_with_value().mir_generator_witnesses(def);
     }
+    // SafeDrop may analyse the bodies before the state transform and drop elaboration, from
+    // the MIR stolen below.
+    if safedrop_mir_reads(tcx, def, SafeDropMirPhase::Cleanup) {
+        tcx.ensure_with_value().safedrop_mir(def);
+    }
     let mir_borrowck = tcx.mir_borrowck(def);
 
     let is_fn_like = tcx.def_kind(def).is_fn_like();
@@ -630,6 +697,11 @@ fn inner_optimized_mir(tcx: TyCtxt<'_>, did: LocalDefId) -> Body<'_> {
         None => {}
         Some(other) => panic!("do not use `optimized_mir` for constants: {other:?}"),
     }
+    if tcx.def_kind(did) != DefKind::Generator
+        && safedrop_mir_reads(tcx, did, SafeDropMirPhase::DropsElaborated)
+    {
+        tcx.ensure_with_value().safedrop_mir(did);
+    }
     debug!("about to call mir_drops_elaborated...");
     let body = tcx.mir_drops_elaborated_and_const_checked(did).steal();
     let mut body = remap_mir_for_const_eval_select(tcx, body, hir::Constness::NotConst);
@@ -1,13 +1,17 @@
 use rustc_middle::ty;
-use rustc_middle::ty::TyCtxt;
-use rustc_middle::mir::{TerminatorKind, Operand, Place, ProjectionElem};
-use rustc_data_structures::fx::FxHashSet;
+use rustc_middle::ty::{Ty, TyCtxt};
+use rustc_middle::mir::tcx::PlaceTy;
+use rustc_middle::mir::{TerminatorKind, Operand, Place, ProjectionElem, SourceInfo};
+pub use rustc_middle::mir::safedrop::{FnRetAlias, RetAlias};
 
 use crate::rap_error;
 use super::graph::*;
 use super::types::*;
 use super::log::*;
 use super::safedrop::*;
+use super::corner_handle::is_leak_fn;
+use super::summary::{callee_summary, resolve_callees};
+use super::spec::{fn_spec, FnSpec, Ownership};
 use log::Log;
 
 impl<'tcx> SafeDropGraph<'tcx>{
@@ -25,27 +29,82 @@ pub fn alias_bb(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>) {
                     self.values[lv_aliaset_idx].alias[0] = rv_aliaset_idx;
                     continue;
                 },
+                AssignType::Value => {
+                    // the copy holds the discriminant of the same value, or the same constant.
+                    let discr_id = self.values[rv_aliaset_idx].alias[0];
+                    self.values[lv_aliaset_idx].alias[0] = if discr_id != rv_aliaset_idx { discr_id } else { lv_aliaset_idx };
+                    self.constant.remove(&lv_aliaset_idx);
+                    self.copy_constant(lv_aliaset_idx, rv_aliaset_idx);
+                    continue;
+                },
+                AssignType::Fresh => {
+                    self.values[lv_aliaset_idx].alias = vec![lv_aliaset_idx];
+                    self.values[lv_aliaset_idx].alias_site = None;
+                    self.values[lv_aliaset_idx].leak_site = None;
+                    self.values[lv_aliaset_idx].dup_site = None;
+                    self.values[lv_aliaset_idx].set_init(InitState::Init, None);
+                    self.fill_birth(lv_aliaset_idx, self.birth_of(bb_index) as isize);
+                    self.constant.remove(&lv_aliaset_idx);
+                    continue;
+                },
+                AssignType::SetDiscriminant(discr) => {
+                    self.constant.insert(lv_aliaset_idx, discr);
+                    continue;
+                },
+                AssignType::StorageDead => {
+                    self.storage_dead(lv_aliaset_idx, assign.span);
+                    continue;
+                },
                 AssignType::InitBox => {
                     lv_aliaset_idx = *self.values[lv_aliaset_idx].fields.get(&0).unwrap();
                 },
                 _ => { }, // Copy or Move
             }
             self.uaf_check(rv_aliaset_idx, assign.span, assign.rv.local.as_usize(), false);
-            self.fill_birth(lv_aliaset_idx, self.scc_indices[bb_index] as isize);
+            self.fill_birth(lv_aliaset_idx, self.birth_of(bb_index) as isize);
+            self.values[lv_aliaset_idx].dup_site = None;
+            self.values[lv_aliaset_idx].set_init(InitState::Init, None);
             if self.values[lv_aliaset_idx].local != self.values[rv_aliaset_idx].local {
                 self.merge_alias(lv_aliaset_idx, rv_aliaset_idx);
+                self.copy_constant(lv_aliaset_idx, rv_aliaset_idx);
+                self.set_alias_site(lv_aliaset_idx, AliasSite { span: assign.span, callee: None });
             }
         }        
     }
 
     /* Check the aliases introduced by the terminators (function call) of a scc block */
-    pub fn alias_bbcall(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>, func_map: &mut FuncMap){
+    pub fn alias_bbcall(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>, func_map: &mut FuncMap<'tcx>){
         let cur_block = self.blocks[bb_index].clone();
         for call in cur_block.calls {
-            if let TerminatorKind::Call { ref func, ref args, ref destination, target:_, unwind: _, call_source: _, fn_span: _ } = call.kind {
-                if let Operand::Constant(ref constant) = func {
+            // a yield moves its value out to the resumer, and the resume argument is a new value from it.
+            if let TerminatorKind::Yield { ref value, ref resume_arg, .. } = call.kind {
+                if let Operand::Copy(ref p) | Operand::Move(ref p) = value {
+                    let rv = self.projection(tcx, true, p.clone());
+                    self.uaf_check(rv, call.source_info.span, p.local.as_usize(), true);
+                }
+                let lv = self.projection(tcx, false, resume_arg.clone());
+                self.values[lv].alias = vec![lv];
+                self.fill_birth(lv, self.birth_of(bb_index) as isize);
+                self.constant.remove(&lv);
+                continue;
+            }
+            if let TerminatorKind::Call { ref func, ref args, ref destination, target:_, unwind, call_source: _, fn_span: _ } = call.kind {
+                // calls through function pointers run the functions stored in them.
+                let targets = call_targets(func, &self.fn_ptrs);
+                if !cur_block.is_cleanup {
+                    self.panic_check(tcx, &targets, unwind, call.source_info.span);
+                }
+                if !targets.is_empty() {
                     let lv = self.projection(tcx, false, destination.clone());
-                    self.values[lv].birth = self.scc_indices[bb_index] as isize;
+                    // the returned value is a new one, with all its fields and none of the aliases of the old one.
+                    self.values[lv].alias = vec![lv];
+                    self.values[lv].alias_site = None;
+                    self.values[lv].leak_site = None;
+                    self.values[lv].dup_site = None;
+                    self.values[lv].set_init(InitState::Init, None);
+                    self.fill_birth(lv, self.birth_of(bb_index) as isize);
+                    // the discriminant of the returned value is unknown.
+                    self.constant.remove(&lv);
                     let mut merge_vec = Vec::new();
                     merge_vec.push(lv);
                     let mut may_drop_flag = 0;
@@ -75,68 +134,142 @@ pub fn alias_bbcall(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>, func_map: &mu
                             },
                         }
                     }
-                    if let ty::FnDef(ref target_id, _) = constant.const_.ty().kind() {
-                        if may_drop_flag > 1 || (may_drop_flag > 0 && Self::should_check(target_id.clone()) == false) {
-                            if tcx.is_mir_available(*target_id) {
-                                if func_map.map.contains_key(&target_id.index.as_usize()) {
-                                    let assignments = func_map.map.get(&target_id.index.as_usize()).unwrap();
-                                    for assign in assignments.alias_vec.iter() {
-                                        if !assign.valuable() {
-                                            continue;
-                                        }
-                                        self.merge(assign, &merge_vec);
-                                    }
-                                    for dead in assignments.dead.iter() {
-                                        let drop = merge_vec[*dead];
-                                        self.dead_node(drop, 99999, &call.source_info, false);
-                                    }
-                                }
-                                else{
-                                    if func_map.set.contains(&target_id.index.as_usize()) {
-                                        continue;
-                                    }
-                                    func_map.set.insert(target_id.index.as_usize());
-                                    let func_body = tcx.optimized_mir(*target_id);
-                                    let mut safedrop_graph = SafeDropGraph::new(&func_body, tcx, *target_id);
-                                    safedrop_graph.solve_scc();
-                                    safedrop_graph.check(0, tcx, func_map);
-                                    let ret_alias = safedrop_graph.ret_alias.clone();
-                                    for assign in ret_alias.alias_vec.iter() {
-                                        if !assign.valuable(){
-                                            continue;
-                                        }
-                                        self.merge(assign, &merge_vec);
-                                    }
-                                    for dead in ret_alias.dead.iter() {
-                                        let drop = merge_vec[*dead];
-                                        self.dead_node(drop, 99999, &call.source_info, false);
-                                    }
-                                    func_map.map.insert(target_id.index.as_usize(), ret_alias);
+                    //the values given up by Box::into_raw, mem::forget and the like are leaked, unless they are freed later.
+                    if let [(target_id, target_args)] = targets[..]
+                        && is_leak_fn(tcx, target_id, target_args)
+                        && merge_vec.len() > 1
+                        && merge_vec[1] != 0
+                        && self.values[merge_vec[1]].need_drop
+                    {
+                        self.values[merge_vec[1]].leak_site = Some(call.source_info.span);
+                        self.end_duplication(merge_vec[1]);
+                    }
+                    self.mark_duplication(tcx, &targets, &merge_vec, args, call.source_info.span);
+                    self.uninit_call(tcx, &targets, &merge_vec, args, call.source_info.span);
+                    //library and FFI functions declared in the -Z safedrop-spec file have the effects declared there.
+                    if let [(target_id, _)] = targets[..] && let Some(spec) = fn_spec(tcx, target_id) {
+                        let old_aliases: Vec<Vec<usize>> = merge_vec.iter().map(|node| self.values[*node].alias.clone()).collect();
+                        self.apply_fn_spec(spec, &merge_vec, &call.source_info);
+                        self.record_alias_site(&merge_vec, old_aliases, AliasSite { span: call.source_info.span, callee: Some(target_id) });
+                        continue;
+                    }
+                    let targets: Vec<_> = targets.into_iter().filter(|(target_id, _)| {
+                        may_drop_flag > 1 || (may_drop_flag > 0 && Self::should_check(tcx, *target_id) == false)
+                    }).collect();
+                    if targets.is_empty() {
+                        continue;
+                    }
+                    //the nodes aliased by the call are recorded in the traces of bug reports.
+                    let old_aliases: Vec<Vec<usize>> = merge_vec.iter().map(|node| self.values[*node].alias.clone()).collect();
+                    let site = AliasSite { span: call.source_info.span, callee: Some(targets[0].0) };
+                    //special functions are modelled directly instead of by their summaries.
+                    if let [(target_id, target_args)] = targets[..] && self.corner_handle(lv, &merge_vec, target_id, target_args) {
+                        self.record_alias_site(&merge_vec, old_aliases, site);
+                        continue;
+                    }
+                    //trait methods run the method of the impl, and calls through trait objects any impl of the trait.
+                    let param_env = self.param_env;
+                    let mut summary: Option<FnRetAlias> = None;
+                    for (target_id, target_args) in targets.iter() {
+                        for instance in resolve_callees(tcx, param_env, *target_id, target_args) {
+                            if let Some(callee) = callee_summary(tcx, instance, func_map) {
+                                match summary {
+                                    Some(ref mut summary) => { summary.join(&callee); },
+                                    None => summary = Some(callee),
                                 }
                             }
-                            else {
-                                if self.values[lv].may_drop {
-                                    if self.corner_handle(lv, &merge_vec, *target_id){
-                                        continue;
-                                    }
-                                    let mut right_set = Vec::new(); 
-                                    for rv in &merge_vec {
-                                        if self.values[*rv].may_drop && lv != *rv && self.values[lv].is_ptr(){
-                                            right_set.push(*rv);
-                                        }
-                                    }
-                                    if right_set.len() == 1 {
-                                        self.merge_alias(lv, right_set[0]);
-                                    }
+                        }
+                    }
+                    if let Some(summary) = summary {
+                        self.merge_call_summary(tcx, &summary, &merge_vec, args, &call.source_info);
+                    }
+                    else {
+                        if self.values[lv].may_drop {
+                            let mut right_set = Vec::new(); 
+                            for rv in &merge_vec {
+                                if self.values[*rv].may_drop && lv != *rv && self.values[lv].is_ptr(){
+                                    right_set.push(*rv);
                                 }
                             }
+                            if right_set.len() == 1 {
+                                self.merge_alias(lv, right_set[0]);
+                            }
                         }
                     }
+                    self.record_alias_site(&merge_vec, old_aliases, site);
                 }
             }
         }
     }
 
+    //record the call as the alias site of the nodes whose aliases it changed.
+    fn record_alias_site(&mut self, merge_vec: &Vec<usize>, old_aliases: Vec<Vec<usize>>, site: AliasSite) {
+        for (i, (node, old_alias)) in merge_vec.iter().zip(old_aliases).enumerate() {
+            //constant arguments are 0 in merge_vec.
+            if (i == 0 || *node != 0) && self.values[*node].alias != old_alias {
+                self.set_alias_site(*node, site);
+            }
+        }
+    }
+
+    //the fields of lv are merged together with lv, so they share its alias site.
+    fn set_alias_site(&mut self, lv: usize, site: AliasSite) {
+        self.values[lv].alias_site = Some(site);
+        for field in self.values[lv].fields.clone().into_values() {
+            self.set_alias_site(field, site);
+        }
+    }
+
+    /*
+     * Apply the summary of a callee to a call, merge_vec holds the destination and the arguments.
+     * A closure called through the Fn traits takes its arguments as one tuple, e.g., `<F as Fn<(A, B)>>::call(&f, move _5)`,
+     * while the summary of its body has one index for each of them.
+     */
+    pub fn merge_call_summary(&mut self, tcx: TyCtxt<'tcx>, summary: &FnRetAlias, merge_vec: &Vec<usize>, args: &[Operand<'tcx>], source_info: &SourceInfo) {
+        if summary.arg_size + 1 == merge_vec.len() {
+            self.merge_ret_alias(summary, merge_vec, source_info);
+            return;
+        }
+        if let [_, _, tuple] = merge_vec[..]
+            && tuple != 0
+            && let Some(Operand::Copy(place) | Operand::Move(place)) = args.get(1)
+            && place.projection.is_empty()
+            && let ty::Tuple(tys) = self.local_tys[place.local.as_usize()].kind()
+            && summary.arg_size == 1 + tys.len()
+        {
+            let mut untupled = vec![merge_vec[0], merge_vec[1]];
+            let local = self.values[tuple].local;
+            for (i, ty) in tys.iter().enumerate() {
+                untupled.push(self.sub_node(tcx, tuple, i, ty, local));
+            }
+            self.merge_ret_alias(summary, &untupled, source_info);
+        }
+    }
+
+    /*
+     * Apply the declared effects of a callee, see `spec`. The arguments in the declaration are numbered from 0,
+     * while merge_vec holds the destination first; constant arguments are 0 in merge_vec and have no effect.
+     */
+    pub fn apply_fn_spec(&mut self, spec: &FnSpec, merge_vec: &Vec<usize>, source_info: &SourceInfo) {
+        let mut summary = FnRetAlias::new(merge_vec.len() - 1);
+        let arg_index = |arg: &usize| Some(arg + 1).filter(|index| *index < merge_vec.len() && merge_vec[*index] != 0);
+        for index in spec.aliases_return.iter().filter_map(arg_index) {
+            summary.alias_vec.push(RetAlias::new(0, 0, true, true, index, true, true));
+        }
+        for index in spec.frees.iter().filter_map(arg_index) {
+            summary.dead.insert(index);
+        }
+        self.merge_ret_alias(&summary, merge_vec, source_info);
+        // the caller owns what an owned return value points to.
+        let lv = merge_vec[0];
+        if self.values[lv].is_ptr() {
+            self.values[lv].need_drop = spec.returns == Ownership::Owned;
+            if spec.returns == Ownership::Owned {
+                self.values[lv].leak_site = Some(source_info.span);
+            }
+        }
+    }
+
     // assign to the variable _x, we will set the birth of _x and its child self.values a new birth.
     pub fn fill_birth(&mut self, node: usize, birth: isize) {
         self.values[node].birth = birth;
@@ -152,48 +285,106 @@ pub fn fill_birth(&mut self, node: usize, birth: isize) {
     }
 
     /*
-     * This is the function for field sensitivity
-     * If the projection is a deref, we directly return its head alias or alias[0].
-     * If the id is not a ref, we further make the id and its first element an alias, i.e., level-insensitive
-     *
+     * This is the function for field and level sensitivity.
+     * If the projection is a deref of a pointer whose target is known, we return its head alias or alias[0].
+     * The contents of a box, and the target of a pointer we know nothing about, are a level of their own:
+     * a node kept under DEREF_FIELD of the pointer, so that `**p` and `*p` are different values.
+     * The fields of an enum variant are kept under the node of the variant, and array or slice elements
+     * under the node of their constant index, or ANY_ELEMENT if the index is not known.
      */
     pub fn projection(&mut self, tcx: TyCtxt<'tcx>, is_right: bool, place: Place<'tcx>) -> usize {
         let mut local = place.local.as_usize();
         let mut proj_id = local;
+        let mut place_ty = PlaceTy::from_ty(self.local_tys[local]);
+        // the start of a subslice in the array or slice it is taken from.
+        let mut elem_offset = 0;
         for proj in place.projection {
-            let new_id = self.values.len();
+            let base_ty = place_ty.ty;
+            place_ty = place_ty.projection_ty(tcx, proj);
             match proj {
                 ProjectionElem::Deref => {
-                    proj_id = self.values[proj_id].alias[0];
-                }
-                /*
-                 * Objective: 2 = 1.0; 0 = 2.0; => 0 = 1.0.0
-                 */
-                ProjectionElem::Field(field, ty) => {
-                    if is_right && self.values[proj_id].alias[0] != proj_id {
-                        proj_id = self.values[proj_id].alias[0];
-                        local = self.values[proj_id].local;
-                    }
-                    let field_idx = field.as_usize();
-                    if !self.values[proj_id].fields.contains_key(&field_idx) {
-                        let param_env = tcx.param_env(self.def_id);
-                        let need_drop = ty.needs_drop(tcx, param_env);
-                        let may_drop = !is_not_drop(tcx, ty);
-                        let mut node = ValueNode::new(new_id, local, need_drop, need_drop || may_drop);
-                        node.kind = kind(ty);
-                        node.birth = self.values[proj_id].birth;
-                        node.field_id = field_idx;
-                        self.values[proj_id].fields.insert(field_idx, node.index);
-                        self.values.push(node);
+                    let target = self.values[proj_id].alias[0];
+                    if self.values[proj_id].is_box() {
+                        // a box moved from another box, or built from a raw pointer, has their contents.
+                        if target == proj_id || self.values[target].is_box() || self.values[target].is_ptr() {
+                            proj_id = self.pointee(tcx, target, place_ty.ty);
+                        } else {
+                            proj_id = target;
+                        }
+                    } else if target != proj_id {
+                        proj_id = target;
+                    } else if self.values[proj_id].is_ptr() {
+                        proj_id = self.pointee(tcx, proj_id, place_ty.ty);
                     }
-                    proj_id = *self.values[proj_id].fields.get(&field_idx).unwrap();
+                    continue;
+                }
+                ProjectionElem::Subslice { from, .. } => {
+                    elem_offset += from as usize;
+                    continue;
                 }
                 _ => {}
             }
+            /*
+             * Objective: 2 = 1.0; 0 = 2.0; => 0 = 1.0.0
+             */
+            if is_right && self.values[proj_id].alias[0] != proj_id {
+                proj_id = self.values[proj_id].alias[0];
+                local = self.values[proj_id].local;
+            }
+            let key = match proj {
+                ProjectionElem::Field(field, _) => field.as_usize(),
+                ProjectionElem::Downcast(_, variant) => variant_key(variant),
+                ProjectionElem::Index(index) => match self.constant.get(&index.as_usize()) {
+                    Some(index) => element_key(*index + elem_offset),
+                    None => ANY_ELEMENT,
+                },
+                ProjectionElem::ConstantIndex { offset, from_end: false, .. } => element_key(offset as usize + elem_offset),
+                ProjectionElem::ConstantIndex { offset, from_end: true, .. } => {
+                    // counted from the end, the index is only known for arrays that are not subsliced.
+                    let param_env = self.param_env;
+                    match base_ty.kind() {
+                        ty::Array(_, len) if elem_offset == 0 => match len.try_eval_target_usize(tcx, param_env) {
+                            Some(len) if len >= offset => element_key((len - offset) as usize),
+                            _ => ANY_ELEMENT,
+                        },
+                        _ => ANY_ELEMENT,
+                    }
+                }
+                _ => continue,
+            };
+            elem_offset = 0;
+            proj_id = self.sub_node(tcx, proj_id, key, place_ty.ty, local);
         }
         return proj_id;
     }
 
+    //the node under `key` in the fields of `parent`, created with the type `ty` if there is none yet.
+    pub fn sub_node(&mut self, tcx: TyCtxt<'tcx>, parent: usize, key: usize, ty: Ty<'tcx>, local: usize) -> usize {
+        if let Some(node) = self.values[parent].fields.get(&key) {
+            return *node;
+        }
+        let param_env = self.param_env;
+        let need_drop = ty.needs_drop(tcx, param_env);
+        let may_drop = !is_not_drop(tcx, ty);
+        let mut node = ValueNode::new(self.values.len(), local, need_drop, need_drop || may_drop);
+        node.kind = kind(tcx, ty);
+        node.birth = self.values[parent].birth;
+        // the parts of memory that is not initialized are not initialized either.
+        node.set_init(self.values[parent].init, self.values[parent].uninit_site);
+        node.father = parent;
+        node.field_id = key;
+        let index = node.index;
+        self.values[parent].fields.insert(key, index);
+        self.values.push(node);
+        return index;
+    }
+
+    //the node of the value `ptr` points to, one level below it.
+    pub fn pointee(&mut self, tcx: TyCtxt<'tcx>, ptr: usize, ty: Ty<'tcx>) -> usize {
+        let local = self.values[ptr].local;
+        return self.sub_node(tcx, ptr, DEREF_FIELD, ty, local);
+    }
+
     //instruction to assign alias for a variable.
     pub fn merge_alias(&mut self, lv: usize, rv: usize) {
         if self.values[lv].alias.len() > 1 {
@@ -207,6 +398,7 @@ pub fn merge_alias(&mut self, lv: usize, rv: usize) {
                 let mut node = ValueNode::new(self.values.len(), self.values[lv].local, self.values[field.1].need_drop, self.values[field.1].may_drop);
                 node.kind = self.values[field.1].kind;
                 node.birth = self.values[lv].birth;
+                node.father = lv;
                 node.field_id = field.0;
                 self.values[lv].fields.insert(field.0, node.index);
                 self.values.push(node);
@@ -215,6 +407,31 @@ pub fn merge_alias(&mut self, lv: usize, rv: usize) {
             self.merge_alias(lv_field, field.1);
         }
     }
+    //the known discriminants of rv and its fields also hold for lv.
+    pub fn copy_constant(&mut self, lv: usize, rv: usize) {
+        if let Some(constant) = self.constant.get(&rv) {
+            self.constant.insert(lv, *constant);
+        }
+        for field in self.values[rv].fields.clone().into_iter() {
+            if let Some(lv_field) = self.values[lv].fields.get(&field.0) {
+                self.copy_constant(*lv_field, field.1);
+            }
+        }
+    }
+    //apply the summary of a callee to the caller, merge_vec holds the destination and the arguments.
+    pub fn merge_ret_alias(&mut self, ret_alias: &FnRetAlias, merge_vec: &Vec<usize>, source_info: &SourceInfo) {
+        for assign in ret_alias.alias_vec.iter() {
+            if !assign.valuable() {
+                continue;
+            }
+            self.merge(assign, merge_vec);
+        }
+        for dead in ret_alias.dead.iter() {
+            let drop = merge_vec[*dead];
+            self.dead_node(drop, 99999, source_info, false);
+        }
+    }
+
     //inter-procedure instruction to merge alias.
     pub fn merge(&mut self, ret_alias: &RetAlias, arg_vec: &Vec<usize>) {
         if ret_alias.left_index >= arg_vec.len() || ret_alias.right_index >= arg_vec.len() {
@@ -232,6 +449,7 @@ pub fn merge(&mut self, ret_alias: &RetAlias, arg_vec: &Vec<usize>) {
                 let mut node = ValueNode::new(self.values.len(), left_init, need_drop, may_drop);
                 node.kind = TyKind::RawPtr;
                 node.birth = self.values[lv].birth;
+                node.father = lv;
                 node.field_id = *index;
                 self.values[lv].fields.insert(*index, node.index);
                 self.values.push(node);
@@ -249,6 +467,7 @@ pub fn merge(&mut self, ret_alias: &RetAlias, arg_vec: &Vec<usize>) {
                 let mut node = ValueNode::new(self.values.len(), right_init, need_drop, may_drop);
                 node.kind = TyKind::RawPtr;
                 node.birth = self.values[rv].birth;
+                node.father = rv;
                 node.field_id = *index;
                 self.values[rv].fields.insert(*index, node.index);
                 self.values.push(node);
@@ -258,62 +477,3 @@ pub fn merge(&mut self, ret_alias: &RetAlias, arg_vec: &Vec<usize>) {
         self.merge_alias(lv, rv);
     }
 }
-/*
- * To store the alias relationships among arguments and return values.
- */
-#[derive(Debug,Clone)]
-pub struct RetAlias{
-    pub left_index: usize,
-    pub left: Vec<usize>, //field
-    pub left_may_drop: bool, 
-    pub left_need_drop: bool,
-    pub right_index: usize,
-    pub right: Vec<usize>,
-    pub right_may_drop: bool, 
-    pub right_need_drop: bool,
-    pub atype: usize,
-}
-
-impl RetAlias{
-    pub fn new(atype: usize, left_index: usize, left_may_drop: bool, left_need_drop: bool,
-        right_index: usize, right_may_drop: bool, right_need_drop: bool) -> RetAlias{
-        let left = Vec::<usize>::new();
-        let right = Vec::<usize>::new();
-        RetAlias{
-            left_index: left_index,
-            left: left,
-            left_may_drop: left_may_drop,
-            left_need_drop: left_need_drop,
-            right_index: right_index,
-            right: right,
-            right_may_drop: right_may_drop,
-            right_need_drop: right_need_drop,
-            atype: atype
-        }
-    }
-
-    pub fn valuable(&self) -> bool{
-        return self.left_may_drop && self.right_may_drop;
-    }
-}
-
-/*
- * To store the alias relationships among arguments and return values.
- * Each function may have multiple return instructions, leading to different RetAlias.
- */
-#[derive(Debug, Clone)]
-pub struct FnRetAlias {
-    pub arg_size: usize,
-    pub alias_vec: Vec<RetAlias>,
-    pub dead: FxHashSet<usize>,
-}
-
-impl FnRetAlias {
-    pub fn new(arg_size: usize) -> FnRetAlias{
-        let alias_vec = Vec::<RetAlias>::new();
-        let dead = FxHashSet::default();
-        FnRetAlias { arg_size: arg_size, alias_vec: alias_vec, dead: dead }
-    }
-}
-
-
@@ -0,0 +1,92 @@
+//! The `rap` tool attributes, with which code states its intent to SafeDrop:
+//! `#[rap::allow(double_free, ..)]` hides the findings of the given checkers,
+//! `#[rap::deallocator]` marks functions that free what their arguments point to, so the
+//! pointers they leave dangling are expected, and `#[rap::trusted]` hides every finding.
+//! On an impl, the attributes apply to all of its methods.
+
+use rustc_ast::{AttrKind, Attribute};
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::SafeDropCheckers;
+use rustc_span::def_id::DefId;
+use rustc_span::symbol::{sym, Symbol};
+
+use crate::errors;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RapAttrs {
+    /// The checkers whose findings are hidden by `#[rap::allow(..)]`.
+    pub allow: SafeDropCheckers,
+    pub deallocator: bool,
+    pub trusted: bool,
+    /// Whether the function or its impl has any `rap` attribute; the heuristics on names and
+    /// paths only apply to functions that have none.
+    pub annotated: bool,
+}
+
+impl RapAttrs {
+    pub fn of(tcx: TyCtxt<'_>, def_id: DefId) -> RapAttrs {
+        let mut attrs = RapAttrs {
+            allow: SafeDropCheckers::none(),
+            deallocator: false,
+            trusted: false,
+            annotated: false,
+        };
+        attrs.collect(tcx, def_id);
+        if let Some(impl_id) = tcx.impl_of_method(def_id) {
+            attrs.collect(tcx, impl_id);
+        }
+        attrs
+    }
+
+    fn collect(&mut self, tcx: TyCtxt<'_>, def_id: DefId) {
+        for attr in tcx.get_attrs_unchecked(def_id) {
+            let Some(name) = rap_attr_name(attr) else {
+                continue;
+            };
+            self.annotated = true;
+            match name {
+                sym::allow => self.collect_allow(tcx, def_id, attr),
+                sym::deallocator => self.deallocator = true,
+                sym::trusted => self.trusted = true,
+                _ => {
+                    //attributes of other crates were checked when they were compiled.
+                    if def_id.is_local() {
+                        tcx.sess.emit_err(errors::SafeDropUnknownAttr { span: attr.span, name });
+                    }
+                }
+            }
+        }
+    }
+
+    fn collect_allow(&mut self, tcx: TyCtxt<'_>, def_id: DefId, attr: &Attribute) {
+        for checker in attr.meta_item_list().unwrap_or_default() {
+            match checker.name_or_empty() {
+                sym::double_free => self.allow.double_free = true,
+                sym::use_after_free => self.allow.use_after_free = true,
+                sym::dangling_pointer => self.allow.dangling_pointer = true,
+                sym::memory_leak => self.allow.memory_leak = true,
+                sym::panic_safety => self.allow.panic_safety = true,
+                sym::uninit_memory => self.allow.uninit_memory = true,
+                name => {
+                    if def_id.is_local() {
+                        tcx.sess.emit_err(errors::SafeDropUnknownChecker {
+                            span: checker.span(),
+                            name,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+//`allow` for `#[rap::allow(..)]`.
+fn rap_attr_name(attr: &Attribute) -> Option<Symbol> {
+    let AttrKind::Normal(ref normal) = attr.kind else {
+        return None;
+    };
+    match &normal.item.path.segments[..] {
+        [tool, name] if tool.ident.name == sym::rap => Some(name.ident.name),
+        _ => None,
+    }
+}
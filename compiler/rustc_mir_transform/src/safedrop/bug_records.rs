@@ -1,57 +1,185 @@
+use rustc_hir::HirId;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::lint::builtin::{DANGLING_POINTER, DOUBLE_FREE, MEMORY_LEAK, PANIC_SAFETY, UNINIT_MEMORY, USE_AFTER_FREE};
 use rustc_span::Span;
-use rustc_span::symbol::Symbol;
-use rustc_data_structures::fx::{FxHashMap, FxHashSet};
-use crate::{rap_warn};
-use super::{log::RapLogLevel, log::record_msg, log::RAP_LOGGER};
+use rustc_span::def_id::DefId;
+use rustc_data_structures::fx::FxIndexMap;
 
-use log::Log;
+use super::graph::AliasSite;
+use super::report::{record_finding, BugKind, Finding};
+use crate::errors;
 
+//the witness of a bug: the blocks of the path to it, and where the value involved got its aliases.
+#[derive(Debug, Clone, Default)]
+pub struct BugTrace {
+    pub path: Vec<usize>,
+    pub alias_sites: Vec<AliasSite>,
+}
+
+impl BugTrace {
+    //the notes and labels of the trace, if they are asked for by -Z safedrop-traces.
+    pub(crate) fn notes(&self, tcx: TyCtxt<'_>) -> Vec<errors::SafeDropTrace> {
+        if !tcx.sess.opts.unstable_opts.safedrop_traces {
+            return Vec::new();
+        }
+        let mut notes = Vec::new();
+        for site in self.alias_sites.iter() {
+            notes.push(match site.callee {
+                Some(callee) => errors::SafeDropTrace::Summary { span: site.span, callee: tcx.def_path_str(callee) },
+                None => errors::SafeDropTrace::Alias { span: site.span },
+            });
+        }
+        if !self.path.is_empty() {
+            notes.push(errors::SafeDropTrace::Path { path: self.path_string() });
+        }
+        notes
+    }
+
+    pub fn path_string(&self) -> String {
+        self.path.iter().map(|block| format!("bb{}", block)).collect::<Vec<_>>().join(" -> ")
+    }
+}
+
+//where a bug is found, together with the drop that freed the value involved (if known).
+#[derive(Debug, Clone)]
+pub struct BugSite {
+    pub span: Span,
+    pub drop_span: Option<Span>,
+    pub trace: BugTrace,
+}
+
+impl BugSite {
+    pub fn new(span: Span, drop_span: Option<Span>, trace: BugTrace) -> BugSite {
+        BugSite { span: span, drop_span: drop_span, trace: trace }
+    }
+}
 
 //structure to record the existed bugs.
 pub struct BugRecords {
-    pub df_bugs: FxHashMap<usize, Span>,
-    pub df_bugs_unwind: FxHashMap<usize, Span>,
-    pub uaf_bugs: FxHashSet<Span>,
-    pub dp_bugs: FxHashSet<Span>,
-    pub dp_bugs_unwind: FxHashSet<Span>,
+    pub df_bugs: FxIndexMap<usize, BugSite>,
+    pub df_bugs_unwind: FxIndexMap<usize, BugSite>,
+    pub uaf_bugs: FxIndexMap<Span, BugSite>,
+    pub dp_bugs: FxIndexMap<Span, BugSite>,
+    pub dp_bugs_unwind: FxIndexMap<Span, BugSite>,
+    //leaked values by the call that gave up their ownership.
+    pub leak_bugs: FxIndexMap<Span, BugSite>,
+    //duplicated ownership by the duplicating call, together with the call that may unwind.
+    pub panic_bugs: FxIndexMap<Span, BugSite>,
+    //reads and drops of memory that is not initialized, together with where the memory was created.
+    pub uninit_read_bugs: FxIndexMap<Span, BugSite>,
+    pub uninit_drop_bugs: FxIndexMap<Span, BugSite>,
 }
 
 impl BugRecords{
     pub fn new() -> BugRecords {
-        BugRecords { df_bugs: FxHashMap::default(), df_bugs_unwind: FxHashMap::default(), uaf_bugs: FxHashSet::default(), dp_bugs: FxHashSet::default(), dp_bugs_unwind: FxHashSet::default()}
+        BugRecords { df_bugs: FxIndexMap::default(), df_bugs_unwind: FxIndexMap::default(), uaf_bugs: FxIndexMap::default(), dp_bugs: FxIndexMap::default(), dp_bugs_unwind: FxIndexMap::default(), leak_bugs: FxIndexMap::default(), panic_bugs: FxIndexMap::default(), uninit_read_bugs: FxIndexMap::default(), uninit_drop_bugs: FxIndexMap::default()}
+    }
+
+    //add the bugs of another analysis of the same function, keeping the bug found first at each span;
+    //double frees are keyed by node, which may differ between the analyses.
+    pub fn merge(&mut self, other: BugRecords) {
+        fn merge_map<K: std::hash::Hash + Eq>(map: &mut FxIndexMap<K, BugSite>, other: FxIndexMap<K, BugSite>) {
+            for (key, site) in other {
+                if !map.values().any(|known| known.span == site.span) {
+                    map.entry(key).or_insert(site);
+                }
+            }
+        }
+        merge_map(&mut self.df_bugs, other.df_bugs);
+        merge_map(&mut self.df_bugs_unwind, other.df_bugs_unwind);
+        merge_map(&mut self.uaf_bugs, other.uaf_bugs);
+        merge_map(&mut self.dp_bugs, other.dp_bugs);
+        merge_map(&mut self.dp_bugs_unwind, other.dp_bugs_unwind);
+        merge_map(&mut self.leak_bugs, other.leak_bugs);
+        merge_map(&mut self.panic_bugs, other.panic_bugs);
+        merge_map(&mut self.uninit_read_bugs, other.uninit_read_bugs);
+        merge_map(&mut self.uninit_drop_bugs, other.uninit_drop_bugs);
     }
 
     pub fn is_bug_free(&self) -> bool {
-        self.df_bugs.is_empty() && self.uaf_bugs.is_empty() && self.dp_bugs.is_empty() && self.dp_bugs_unwind.is_empty()
+        self.df_bugs.is_empty() && self.uaf_bugs.is_empty() && self.dp_bugs.is_empty() && self.dp_bugs_unwind.is_empty() && self.leak_bugs.is_empty() && self.panic_bugs.is_empty() && self.uninit_read_bugs.is_empty() && self.uninit_drop_bugs.is_empty()
     }
 
-    pub fn df_bugs_output(&self, fn_name:Symbol) {
-        if !self.df_bugs.is_empty() {
-            rap_warn!("Double free detected in function {:}", fn_name);
-            for i in self.df_bugs.iter() {
-                rap_warn!("Location: {:?}", i.1);
-            }
+    pub fn df_bugs_output(&self, tcx: TyCtxt<'_>, def_id: DefId, hir_id: HirId, findings: &mut Vec<Finding>) {
+        for site in self.df_bugs.values() {
+            tcx.emit_spanned_lint(
+                DOUBLE_FREE,
+                hir_id,
+                site.span,
+                errors::SafeDropDoubleFree { span: site.span, drop_span: site.drop_span, trace: site.trace.notes(tcx) },
+            );
+            findings.extend(record_finding(tcx, def_id, hir_id, BugKind::DoubleFree, site, false));
         }
     }
 
-    pub fn uaf_bugs_output(&self, fn_name:Symbol) {
-        if !self.uaf_bugs.is_empty() {
-            rap_warn!("Use after free detected in function {:?}", fn_name);
-            for i in self.uaf_bugs.iter() {
-                rap_warn!("Location: {:?}", i);
-            }
+    pub fn uaf_bugs_output(&self, tcx: TyCtxt<'_>, def_id: DefId, hir_id: HirId, findings: &mut Vec<Finding>) {
+        for site in self.uaf_bugs.values() {
+            tcx.emit_spanned_lint(
+                USE_AFTER_FREE,
+                hir_id,
+                site.span,
+                errors::SafeDropUseAfterFree { span: site.span, drop_span: site.drop_span, trace: site.trace.notes(tcx) },
+            );
+            findings.extend(record_finding(tcx, def_id, hir_id, BugKind::UseAfterFree, site, false));
+        }
+    }
+
+    pub fn dp_bug_output(&self, tcx: TyCtxt<'_>, def_id: DefId, hir_id: HirId, findings: &mut Vec<Finding>) {
+        for site in self.dp_bugs.values() {
+            tcx.emit_spanned_lint(
+                DANGLING_POINTER,
+                hir_id,
+                site.span,
+                errors::SafeDropDanglingPointer { drop_span: site.drop_span, unwind: false, trace: site.trace.notes(tcx) },
+            );
+            findings.extend(record_finding(tcx, def_id, hir_id, BugKind::DanglingPointer, site, false));
+        }
+        for site in self.dp_bugs_unwind.values() {
+            tcx.emit_spanned_lint(
+                DANGLING_POINTER,
+                hir_id,
+                site.span,
+                errors::SafeDropDanglingPointer { drop_span: site.drop_span, unwind: true, trace: site.trace.notes(tcx) },
+            );
+            findings.extend(record_finding(tcx, def_id, hir_id, BugKind::DanglingPointer, site, true));
         }
+    }
 
+    pub fn leak_bugs_output(&self, tcx: TyCtxt<'_>, def_id: DefId, hir_id: HirId, findings: &mut Vec<Finding>) {
+        for site in self.leak_bugs.values() {
+            tcx.emit_spanned_lint(
+                MEMORY_LEAK,
+                hir_id,
+                site.span,
+                errors::SafeDropMemoryLeak { span: site.span, trace: site.trace.notes(tcx) },
+            );
+            findings.extend(record_finding(tcx, def_id, hir_id, BugKind::MemoryLeak, site, false));
+        }
     }
 
-    pub fn dp_bug_output(&self, fn_name:Symbol) {
-        for i in self.dp_bugs.iter() {
-            rap_warn!("Dangling pointer detected in function {:?}!!! 
-                      Location: {:?}", fn_name, i);
+    pub fn panic_bugs_output(&self, tcx: TyCtxt<'_>, def_id: DefId, hir_id: HirId, findings: &mut Vec<Finding>) {
+        for site in self.panic_bugs.values() {
+            tcx.emit_spanned_lint(
+                PANIC_SAFETY,
+                hir_id,
+                site.span,
+                errors::SafeDropPanicSafety { span: site.span, call_span: site.drop_span, trace: site.trace.notes(tcx) },
+            );
+            findings.extend(record_finding(tcx, def_id, hir_id, BugKind::PanicSafety, site, false));
         }
-        for i in self.dp_bugs_unwind.iter() {
-            rap_warn!("Dangling pointer detected in function {:?}!!!
-                      Location: {:?} during unwinding.", fn_name, i);
+    }
+
+    pub fn uninit_bugs_output(&self, tcx: TyCtxt<'_>, def_id: DefId, hir_id: HirId, findings: &mut Vec<Finding>) {
+        for (bugs, is_drop) in [(&self.uninit_read_bugs, false), (&self.uninit_drop_bugs, true)] {
+            for site in bugs.values() {
+                tcx.emit_spanned_lint(
+                    UNINIT_MEMORY,
+                    hir_id,
+                    site.span,
+                    errors::SafeDropUninitMemory { span: site.span, is_drop: is_drop, uninit_span: site.drop_span, trace: site.trace.notes(tcx) },
+                );
+                findings.extend(record_finding(tcx, def_id, hir_id, BugKind::UninitMemory, site, false));
+            }
         }
     }
 }
@@ -1,29 +1,65 @@
-use rustc_middle::mir::SourceInfo;
+use std::collections::VecDeque;
+use rustc_middle::mir::{Operand, SourceInfo, UnwindAction};
+use rustc_middle::ty::{GenericArgsRef, TyCtxt};
 use rustc_span::Span;
-use rustc_span::symbol::Symbol;
-use rustc_data_structures::fx::FxHashSet;
+use rustc_span::def_id::DefId;
+use rustc_target::spec::PanicStrategy;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_session::config::SafeDropCheckers;
 
 use super::graph::*;
 use super::utils::*;
 use super::alias::*;
+use super::bug_records::*;
+use super::attrs::RapAttrs;
+use super::corner_handle::{special_fn, SpecialFnKind};
+use super::report::Finding;
 
-impl<'tcx> SafeDropGraph<'tcx> {
-    pub fn report_bugs(&self) {
-	    let filename = get_filename(self.tcx, self.def_id);
+// report the bugs recorded for `def_id`, unless they are hidden by its attributes or it is a dependency,
+// and return their findings for `-Z safedrop-report`.
+pub fn report_bug_records(tcx: TyCtxt<'_>, def_id: DefId, bug_records: &BugRecords) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let attrs = RapAttrs::of(tcx, def_id);
+    if attrs.trusted {
+        return findings;
+    }
+    // without annotations, the code of dependencies is not reported.
+    if !attrs.annotated {
+        let filename = get_filename(tcx, def_id);
         match filename {
-	        Some(filename) => { if filename.contains(".cargo") { return; } },
+            Some(filename) => { if filename.contains(".cargo") { return findings; } },
             None => {},
         }
-        if self.bug_records.is_bug_free(){
-            return;
-        }
-        let fn_name = match get_fn_name(self.tcx, self.def_id) {
-            Some(name) => name,
-            None => Symbol::intern("no symbol available"),
-        };
-        self.bug_records.df_bugs_output(fn_name);
-        self.bug_records.uaf_bugs_output(fn_name);
-        self.bug_records.dp_bug_output(fn_name);
+    }
+    if bug_records.is_bug_free(){
+        return findings;
+    }
+    let hir_id = tcx.hir().local_def_id_to_hir_id(def_id.expect_local());
+    let checkers = tcx.sess.opts.unstable_opts.safedrop.unwrap_or(SafeDropCheckers::all());
+    if checkers.double_free && !attrs.allow.double_free {
+        bug_records.df_bugs_output(tcx, def_id, hir_id, &mut findings);
+    }
+    if checkers.use_after_free && !attrs.allow.use_after_free {
+        bug_records.uaf_bugs_output(tcx, def_id, hir_id, &mut findings);
+    }
+    if checkers.dangling_pointer && !attrs.allow.dangling_pointer {
+        bug_records.dp_bug_output(tcx, def_id, hir_id, &mut findings);
+    }
+    if checkers.memory_leak && !attrs.allow.memory_leak {
+        bug_records.leak_bugs_output(tcx, def_id, hir_id, &mut findings);
+    }
+    if checkers.panic_safety && !attrs.allow.panic_safety {
+        bug_records.panic_bugs_output(tcx, def_id, hir_id, &mut findings);
+    }
+    if checkers.uninit_memory && !attrs.allow.uninit_memory {
+        bug_records.uninit_bugs_output(tcx, def_id, hir_id, &mut findings);
+    }
+    findings
+}
+
+impl<'tcx> SafeDropGraph<'tcx> {
+    pub fn report_bugs(&self) -> Vec<Finding> {
+        report_bug_records(self.tcx, self.def_id, &self.bug_records)
     }
 
     pub fn uaf_check(&mut self, aliaset_idx: usize, span: Span, local: usize, is_func_call: bool) {
@@ -32,63 +68,117 @@ pub fn uaf_check(&mut self, aliaset_idx: usize, span: Span, local: usize, is_fun
             && (!self.values[aliaset_idx].is_ptr() 
                 || self.values[aliaset_idx].local != local
                 || is_func_call)
-            && self.exist_dead(aliaset_idx, &mut record, false)
-            && !self.bug_records.uaf_bugs.contains(&span) {            
-            self.bug_records.uaf_bugs.insert(span.clone());
+            && !self.bug_records.uaf_bugs.contains_key(&span) {            
+            if let Some(dead) = self.find_dead(aliaset_idx, &mut record, false) {
+                let site = BugSite::new(span, self.values[dead].drop_span, self.trace(aliaset_idx, dead));
+                self.bug_records.uaf_bugs.insert(span, site);
+            }
         }
     }
 
-    pub fn exist_dead(&self, node: usize, record: &mut FxHashSet<usize>, dangling: bool) -> bool {
+    // return the first dead node reachable from `node` through its aliases and fields.
+    pub fn find_dead(&self, node: usize, record: &mut FxHashSet<usize>, dangling: bool) -> Option<usize> {
         //if is a dangling pointer check, only check the pointer type varible.
         if self.values[node].is_alive() == false && (dangling && self.values[node].is_ptr() || !dangling) {
-            return true; 
+            return Some(node); 
         }
         record.insert(node);
         if self.values[node].alias[0] != node {
             for i in self.values[node].alias.clone().into_iter() {
-                if i != node && record.contains(&i) == false && self.exist_dead(i, record, dangling) {
-                    return true;
+                if i != node && record.contains(&i) == false {
+                    if let Some(dead) = self.find_dead(i, record, dangling) {
+                        return Some(dead);
+                    }
                 }
             }
         }
         for i in self.values[node].fields.clone().into_iter() {
-            if record.contains(&i.1) == false && self.exist_dead(i.1, record, dangling) {
-                return true;
+            if record.contains(&i.1) == false {
+                if let Some(dead) = self.find_dead(i.1, record, dangling) {
+                    return Some(dead);
+                }
             }
         }
-        return false;
+        return None;
     }
 
-    pub fn is_dangling(&self, local: usize) -> bool {
+    /*
+     * The witness of a bug found from `node` at the dead node `dead`: the path being checked, and the sites that
+     * aliased the nodes on a shortest chain of aliases and fields between them, from `node` on.
+     */
+    pub fn trace(&self, node: usize, dead: usize) -> BugTrace {
+        let mut parent = FxHashMap::default();
+        let mut queue = VecDeque::from([node]);
+        parent.insert(node, node);
+        while let Some(cur) = queue.pop_front() {
+            if cur == dead {
+                break;
+            }
+            let aliases = self.values[cur].alias.iter().filter(|alias| **alias != cur);
+            for next in aliases.chain(self.values[cur].fields.values()) {
+                if !parent.contains_key(next) {
+                    parent.insert(*next, cur);
+                    queue.push_back(*next);
+                }
+            }
+        }
+        let mut alias_sites = Vec::new();
+        let mut cur = dead;
+        loop {
+            if let Some(site) = self.values[cur].alias_site && !alias_sites.contains(&site) {
+                alias_sites.push(site);
+            }
+            match parent.get(&cur) {
+                Some(prev) if *prev != cur => cur = *prev,
+                _ => break,
+            }
+        }
+        alias_sites.reverse();
+        BugTrace { path: self.path.clone(), alias_sites: alias_sites }
+    }
+
+    pub fn find_dangling(&self, local: usize) -> Option<usize> {
         let mut record = FxHashSet::default();
-        return self.exist_dead(local, &mut record, local != 0);
+        return self.find_dead(local, &mut record, local != 0);
     }
 
     pub fn df_check(&mut self, drop: usize, span: Span) -> bool {
         let root = self.values[drop].local;
         if self.values[drop].is_alive() == false 
         && self.bug_records.df_bugs.contains_key(&root) == false {
-            self.bug_records.df_bugs.insert(root, span.clone());
+            let site = BugSite::new(span, self.values[drop].drop_span, self.trace(drop, drop));
+            self.bug_records.df_bugs.insert(root, site);
         }
         return self.values[drop].is_alive() == false;
     }
 
     pub fn dp_check(&mut self, current_block: &BlockNode<'tcx>) {
+        let span = self.tcx.def_span(self.def_id);
         match current_block.is_cleanup {
             true => {
                 for i in 0..self.arg_size {
-                    if self.values[i+1].is_ptr() && self.is_dangling(i+1) {
-                        self.bug_records.dp_bugs_unwind.insert(self.span);
+                    if !self.values[i+1].is_ptr() {
+                        continue;
+                    }
+                    if let Some(dead) = self.find_dangling(i+1) {
+                        let site = BugSite::new(span, self.values[dead].drop_span, self.trace(i+1, dead));
+                        self.bug_records.dp_bugs_unwind.entry(span).or_insert(site);
                     }
                 }
             },
             false => { 
-                if self.values[0].may_drop && self.is_dangling(0) {
-                    self.bug_records.dp_bugs.insert(self.span);
+                let ret_dead = if self.values[0].may_drop && !current_block.is_generator_drop { self.find_dangling(0) } else { None };
+                if let Some(dead) = ret_dead {
+                    let site = BugSite::new(span, self.values[dead].drop_span, self.trace(0, dead));
+                    self.bug_records.dp_bugs.entry(span).or_insert(site);
                 } else{
                     for i in 0..self.arg_size {
-                        if self.values[i+1].is_ptr() && self.is_dangling(i+1) {
-                            self.bug_records.dp_bugs.insert(self.span);
+                        if !self.values[i+1].is_ptr() {
+                            continue;
+                        }
+                        if let Some(dead) = self.find_dangling(i+1) {
+                            let site = BugSite::new(span, self.values[dead].drop_span, self.trace(i+1, dead));
+                            self.bug_records.dp_bugs.entry(span).or_insert(site);
                         }
                     }
                 }
@@ -96,11 +186,144 @@ pub fn dp_check(&mut self, current_block: &BlockNode<'tcx>) {
         }
     }
 
+    /*
+     * Values whose ownership was given up, e.g., by Box::into_raw, are leaked if the function returns
+     * before they are freed, returned, or stored behind a pointer argument. A value is freed if it or
+     * what it points to is dead, e.g., after the box taken back by Box::from_raw is dropped.
+     */
+    pub fn leak_check(&mut self, current_block: &BlockNode<'tcx>) {
+        if !current_block.is_return {
+            return;
+        }
+        let mut roots = vec![0];
+        roots.extend((1..self.arg_size + 1).filter(|arg| self.values[*arg].is_ptr()));
+        let visible = self.reachable(roots);
+        for node in 0..self.values.len() {
+            let Some(span) = self.values[node].leak_site else {
+                continue;
+            };
+            if self.is_freed(node) || self.reachable(vec![node]).iter().any(|reached| visible.contains(reached)) {
+                continue;
+            }
+            let site = BugSite::new(span, None, self.trace(node, node));
+            self.bug_records.leak_bugs.entry(span).or_insert(site);
+        }
+    }
+
+    /*
+     * Ownership duplicated bit-wise by ptr::read, ptr::copy or Vec::set_len is only safe if the duplicate is
+     * written back, e.g., by ptr::write, before anything drops the value. If a call in between unwinds, the
+     * cleanup blocks of this function or of its callers drop both owners, or drop elements that are not
+     * initialized yet. Calls that cannot unwind, e.g., in a crate built with `-C panic=abort`, are fine.
+     */
+    pub fn panic_check(&mut self, tcx: TyCtxt<'tcx>, targets: &Vec<(DefId, GenericArgsRef<'tcx>)>, unwind: UnwindAction, span: Span) {
+        if tcx.sess.panic_strategy() == PanicStrategy::Abort {
+            return;
+        }
+        if !matches!(unwind, UnwindAction::Cleanup(_) | UnwindAction::Continue) {
+            return;
+        }
+        // the duplicating, restoring and leaking functions do not panic themselves.
+        if let [(target_id, target_args)] = targets[..]
+            && let Some(special) = special_fn(tcx, target_id, target_args)
+            && matches!(special.kind, SpecialFnKind::Duplicate | SpecialFnKind::Restore | SpecialFnKind::Leak)
+        {
+            return;
+        }
+        for node in 0..self.values.len() {
+            let Some(dup_span) = self.values[node].dup_site else {
+                continue;
+            };
+            let site = BugSite::new(dup_span, Some(span), self.trace(node, node));
+            self.bug_records.panic_bugs.entry(dup_span).or_insert(site);
+        }
+    }
+
+    //record where the ownership of a value that needs drop is duplicated, and forget it once it is restored.
+    pub fn mark_duplication(&mut self, tcx: TyCtxt<'tcx>, targets: &Vec<(DefId, GenericArgsRef<'tcx>)>, merge_vec: &Vec<usize>, args: &[Operand<'tcx>], span: Span) {
+        let [(target_id, target_args)] = targets[..] else {
+            return;
+        };
+        if merge_vec.len() < 2 || merge_vec[1] == 0 {
+            return;
+        }
+        let ptr = merge_vec[1];
+        match special_fn(tcx, target_id, target_args).map(|special| special.kind) {
+            Some(SpecialFnKind::Duplicate) => {
+                let param_env = self.param_env;
+                if target_args.is_empty() || !target_args.type_at(0).needs_drop(tcx, param_env) {
+                    return;
+                }
+                // a copy of no elements, or `set_len(0)`, duplicates nothing.
+                if is_zero_count(tcx, param_env, args) {
+                    return;
+                }
+                self.values[ptr].dup_site = Some(span);
+                //the duplicate returned by ptr::read is the other owner.
+                if self.values[merge_vec[0]].need_drop {
+                    self.values[merge_vec[0]].dup_site = Some(span);
+                }
+            },
+            Some(SpecialFnKind::Restore) => {
+                for node in 0..self.values.len() {
+                    if self.values[node].dup_site.is_some()
+                        && (node == ptr || self.values[ptr].alias.contains(&node) || self.values[node].alias.contains(&ptr))
+                    {
+                        self.end_duplication(node);
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+
+    //the ownership is not duplicated any more once one of the owners is written back, forgotten or dropped.
+    pub fn end_duplication(&mut self, node: usize) {
+        let Some(span) = self.values[node].dup_site else {
+            return;
+        };
+        for value in self.values.iter_mut() {
+            if value.dup_site == Some(span) {
+                value.dup_site = None;
+            }
+        }
+    }
+
+    fn is_freed(&self, node: usize) -> bool {
+        let mut cur = node;
+        let mut record = FxHashSet::default();
+        while record.insert(cur) {
+            if !self.values[cur].is_alive() {
+                return true;
+            }
+            match self.values[cur].fields.get(&DEREF_FIELD) {
+                Some(pointee) => cur = *pointee,
+                None => break,
+            }
+        }
+        false
+    }
+
+    // the values reachable from the given ones through their aliases and fields.
+    fn reachable(&self, roots: Vec<usize>) -> FxHashSet<usize> {
+        let mut reached: FxHashSet<usize> = roots.iter().copied().collect();
+        let mut stack = roots;
+        while let Some(cur) = stack.pop() {
+            for next in self.values[cur].alias.iter().chain(self.values[cur].fields.values()) {
+                if reached.insert(*next) {
+                    stack.push(*next);
+                }
+            }
+        }
+        reached
+    }
+
     pub fn dead_node(&mut self, drop: usize, birth: usize, info: &SourceInfo, alias: bool) {
-        //Rc drop
-        if self.values[drop].is_corner_case() {
+        //dropping a weak pointer or a RefCell guard only releases a count or a borrow.
+        if !self.values[drop].frees_on_drop() {
             return;
         } 
+        self.end_duplication(drop);
         //check if there is a double free bug.
         if self.df_check(drop, info.span) {
             return;
@@ -123,10 +346,45 @@ pub fn dead_node(&mut self, drop: usize, birth: usize, info: &SourceInfo, alias:
                 } 
                 self.dead_node( i.1, birth, info, false);
             }
+        } else if let Some(pointee) = self.values[drop].fields.get(&DEREF_FIELD) {
+            //but what an alias points to is freed with it, e.g., the raw pointer a box was built from.
+            self.dead_node(*pointee, birth, info, false);
         }
         //SCC.
         if self.values[drop].birth < birth as isize && self.values[drop].may_drop {
-            self.values[drop].dead();   
+            self.values[drop].dead(info.span);   
+        }
+    }
+
+    /*
+     * The storage of a local ends: the values in its stack slot are dead, so that pointers to them dangle.
+     * What the local owns on the heap is not, and neither is a value that was moved or copied out of the slot,
+     * i.e., that a value of another local aliases. Pointers in the slot are copied far more often than borrowed,
+     * so they are kept as well.
+     */
+    pub fn storage_dead(&mut self, local: usize, span: Span) {
+        let mut slot = Vec::new();
+        let mut stack = vec![local];
+        while let Some(node) = stack.pop() {
+            slot.push(node);
+            for (key, field) in self.values[node].fields.iter() {
+                if *key != DEREF_FIELD {
+                    stack.push(*field);
+                }
+            }
+        }
+        //the values that a value of another local aliases, collected once rather than for each node of the slot.
+        let moved: FxHashSet<usize> = self.values.iter()
+            .filter(|value| value.local != local && !value.is_ptr())
+            .flat_map(|value| value.alias.iter().copied())
+            .collect();
+        for node in slot {
+            if self.values[node].is_ptr() || !self.values[node].is_alive() {
+                continue;
+            }
+            if !moved.contains(&node) {
+                self.values[node].dead(span);
+            }
         }
     }
 
@@ -165,8 +423,9 @@ pub fn get_field_seq(&self, value: &ValueNode)-> Vec<usize> {
         let mut node_ref = value;
         while node_ref.field_id != usize::MAX {
             field_id_seq.push(node_ref.field_id);
-            node_ref = &self.values[value.father]; 
+            node_ref = &self.values[node_ref.father]; 
         }
+        field_id_seq.reverse();
         return field_id_seq;
     }
 }
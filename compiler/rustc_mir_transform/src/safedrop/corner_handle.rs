@@ -1,63 +1,322 @@
+use rustc_hir::LangItem;
+use rustc_middle::ty::{self, GenericArgsRef, TyCtxt};
+use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_span::def_id::DefId;
-use super::safedrop::*;
+use rustc_span::symbol::{sym, Symbol};
 use super::graph::*;
+use super::attrs::RapAttrs;
+
+/*
+ * How SafeDrop should treat a call to a special library function.
+ */
+#[derive(PartialEq,Debug,Copy,Clone)]
+pub enum SpecialFnKind {
+    // the function drops the value passed as its first argument.
+    Drop,
+    // the function neither aliases nor drops its arguments, e.g., it has no MIR to analyze,
+    // or it returns a new owner of a reference count.
+    NoEffect,
+    // the returned value aliases the first argument, which is not dropped.
+    Alias,
+    // the function gives up the ownership of its first argument without dropping it,
+    // so the value is leaked unless it is freed or given back to the caller later.
+    Leak,
+    // the function duplicates bit-wise the ownership of what its first argument points to,
+    // so the value has two owners until one of them is overwritten without being dropped.
+    Duplicate,
+    // the function moves a value to where its first argument points without dropping the old one,
+    // which ends a duplication of the ownership of the old value.
+    Restore,
+}
+
+/*
+ * How a special function is resolved to a DefId.
+ * We never compare DefIndex values, as they change with every libstd build
+ * and are meaningless for any other crate.
+ */
+#[derive(Debug,Copy,Clone)]
+pub enum SpecialItem {
+    // a function that is a lang item.
+    Lang(LangItem),
+    // a function marked with `#[rustc_diagnostic_item]`.
+    Diagnostic(Symbol),
+    // a method of a lang item trait, given by its name.
+    TraitMethod(LangItem, Symbol),
+    // a method of a type marked with `#[rustc_diagnostic_item]`, including the trait methods
+    // called on that type, e.g., `Clone::clone` on `Rc`.
+    Method(Symbol, Symbol),
+    // a method of a lang item type.
+    LangMethod(LangItem, Symbol),
+    // a function that has neither, given by its path, e.g., `alloc::alloc::alloc`.
+    Path(&'static str),
+}
+
+#[derive(Debug)]
+pub struct SpecialFn {
+    // the stable path of the function, which is also the key of the registry.
+    pub path: &'static str,
+    pub item: SpecialItem,
+    pub kind: SpecialFnKind,
+}
+
+/*
+ * The registry of functions that SafeDrop models directly instead of analyzing their MIR.
+ * To support a new function, add an entry here.
+ */
+pub static SPECIAL_FNS: &[SpecialFn] = &[
+    SpecialFn {
+        path: "core::mem::drop",
+        item: SpecialItem::Diagnostic(sym::mem_drop),
+        kind: SpecialFnKind::Drop,
+    },
+    SpecialFn {
+        path: "core::ptr::drop_in_place",
+        item: SpecialItem::Lang(LangItem::DropInPlace),
+        kind: SpecialFnKind::Drop,
+    },
+    // #![feature(fn_traits)]
+    // fn main() {
+    //     let x = 1i32;
+    //     let mut c = || {x+1;};
+    //     c.call_mut(());
+    // }
+    SpecialFn {
+        path: "core::ops::FnMut::call_mut",
+        item: SpecialItem::TraitMethod(LangItem::FnMut, sym::call_mut),
+        kind: SpecialFnKind::NoEffect,
+    },
+    SpecialFn {
+        path: "core::iter::Iterator::next",
+        item: SpecialItem::Lang(LangItem::IteratorNext),
+        kind: SpecialFnKind::NoEffect,
+    },
+    /*
+     * Reference-counted pointers.
+     * Each value that owns a count is its own node, so dropping a clone does not kill the original.
+     * A raw pointer from `into_raw` keeps the count of the pointer it came from, and `from_raw`
+     * takes that count back; two `from_raw` of the same pointer therefore alias each other.
+     */
+    SpecialFn {
+        path: "alloc::rc::Rc::clone",
+        item: SpecialItem::Method(sym::Rc, sym::clone),
+        kind: SpecialFnKind::NoEffect,
+    },
+    SpecialFn {
+        path: "alloc::rc::Rc::downgrade",
+        item: SpecialItem::Method(sym::Rc, sym::downgrade),
+        kind: SpecialFnKind::NoEffect,
+    },
+    SpecialFn {
+        path: "alloc::rc::Rc::into_raw",
+        item: SpecialItem::Method(sym::Rc, sym::into_raw),
+        kind: SpecialFnKind::Alias,
+    },
+    SpecialFn {
+        path: "alloc::rc::Rc::from_raw",
+        item: SpecialItem::Method(sym::Rc, sym::from_raw),
+        kind: SpecialFnKind::Alias,
+    },
+    SpecialFn {
+        path: "alloc::rc::Rc::get_mut_unchecked",
+        item: SpecialItem::Method(sym::Rc, sym::get_mut_unchecked),
+        kind: SpecialFnKind::Alias,
+    },
+    SpecialFn {
+        path: "alloc::rc::Weak::upgrade",
+        item: SpecialItem::Method(sym::RcWeak, sym::upgrade),
+        kind: SpecialFnKind::NoEffect,
+    },
+    SpecialFn {
+        path: "alloc::sync::Arc::clone",
+        item: SpecialItem::Method(sym::Arc, sym::clone),
+        kind: SpecialFnKind::NoEffect,
+    },
+    SpecialFn {
+        path: "alloc::sync::Arc::downgrade",
+        item: SpecialItem::Method(sym::Arc, sym::downgrade),
+        kind: SpecialFnKind::NoEffect,
+    },
+    SpecialFn {
+        path: "alloc::sync::Arc::into_raw",
+        item: SpecialItem::Method(sym::Arc, sym::into_raw),
+        kind: SpecialFnKind::Alias,
+    },
+    SpecialFn {
+        path: "alloc::sync::Arc::from_raw",
+        item: SpecialItem::Method(sym::Arc, sym::from_raw),
+        kind: SpecialFnKind::Alias,
+    },
+    SpecialFn {
+        path: "alloc::sync::Arc::get_mut_unchecked",
+        item: SpecialItem::Method(sym::Arc, sym::get_mut_unchecked),
+        kind: SpecialFnKind::Alias,
+    },
+    SpecialFn {
+        path: "alloc::sync::Weak::upgrade",
+        item: SpecialItem::Method(sym::ArcWeak, sym::upgrade),
+        kind: SpecialFnKind::NoEffect,
+    },
+    // values that are never dropped, unless their owner is taken back, e.g., by `Box::from_raw`.
+    SpecialFn {
+        path: "core::mem::forget",
+        item: SpecialItem::Diagnostic(sym::mem_forget),
+        kind: SpecialFnKind::Leak,
+    },
+    SpecialFn {
+        path: "core::mem::ManuallyDrop::new",
+        item: SpecialItem::LangMethod(LangItem::ManuallyDrop, sym::new),
+        kind: SpecialFnKind::Leak,
+    },
+    SpecialFn {
+        path: "alloc::boxed::Box::into_raw",
+        item: SpecialItem::LangMethod(LangItem::OwnedBox, sym::into_raw),
+        kind: SpecialFnKind::Leak,
+    },
+    SpecialFn {
+        path: "alloc::boxed::Box::leak",
+        item: SpecialItem::LangMethod(LangItem::OwnedBox, sym::leak),
+        kind: SpecialFnKind::Leak,
+    },
+    SpecialFn {
+        path: "alloc::vec::Vec::leak",
+        item: SpecialItem::Method(sym::Vec, sym::leak),
+        kind: SpecialFnKind::Leak,
+    },
+    // ownership duplicated bit-wise, e.g., by reading a value out of a vector, or by growing a vector
+    // over elements that are not initialized yet; see `panic_check`.
+    SpecialFn {
+        path: "core::ptr::read",
+        item: SpecialItem::Diagnostic(sym::ptr_read),
+        kind: SpecialFnKind::Duplicate,
+    },
+    SpecialFn {
+        path: "core::ptr::read_unaligned",
+        item: SpecialItem::Diagnostic(sym::ptr_read_unaligned),
+        kind: SpecialFnKind::Duplicate,
+    },
+    SpecialFn {
+        path: "core::intrinsics::copy",
+        item: SpecialItem::Diagnostic(sym::ptr_copy),
+        kind: SpecialFnKind::Duplicate,
+    },
+    SpecialFn {
+        path: "core::intrinsics::copy_nonoverlapping",
+        item: SpecialItem::Diagnostic(sym::ptr_copy_nonoverlapping),
+        kind: SpecialFnKind::Duplicate,
+    },
+    SpecialFn {
+        path: "alloc::vec::Vec::set_len",
+        item: SpecialItem::Method(sym::Vec, sym::set_len),
+        kind: SpecialFnKind::Duplicate,
+    },
+    SpecialFn {
+        path: "core::ptr::write",
+        item: SpecialItem::Diagnostic(sym::ptr_write),
+        kind: SpecialFnKind::Restore,
+    },
+    SpecialFn {
+        path: "core::ptr::write_unaligned",
+        item: SpecialItem::Diagnostic(sym::ptr_write_unaligned),
+        kind: SpecialFnKind::Restore,
+    },
+    SpecialFn {
+        path: "core::mem::ManuallyDrop::drop",
+        item: SpecialItem::LangMethod(LangItem::ManuallyDrop, sym::drop),
+        kind: SpecialFnKind::Drop,
+    },
+    SpecialFn {
+        path: "core::mem::ManuallyDrop::take",
+        item: SpecialItem::LangMethod(LangItem::ManuallyDrop, sym::take),
+        kind: SpecialFnKind::Alias,
+    },
+];
+
+// the type a method is called on: the self type of its impl, or the `Self` argument of a trait method.
+fn method_self_adt<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId, args: GenericArgsRef<'tcx>) -> Option<DefId> {
+    let self_ty = if let Some(impl_id) = tcx.impl_of_method(def_id) {
+        tcx.type_of(impl_id).instantiate_identity()
+    } else if tcx.trait_of_item(def_id).is_some() && !args.is_empty() {
+        args.type_at(0)
+    } else {
+        return None;
+    };
+    match self_ty.kind() {
+        ty::Adt(adt_def, _) => Some(adt_def.did()),
+        _ => None,
+    }
+}
+
+impl SpecialItem {
+    pub fn matches<'tcx>(&self, tcx: TyCtxt<'tcx>, def_id: DefId, args: GenericArgsRef<'tcx>) -> bool {
+        match *self {
+            SpecialItem::Lang(item) => tcx.lang_items().get(item) == Some(def_id),
+            SpecialItem::Diagnostic(name) => tcx.is_diagnostic_item(name, def_id),
+            SpecialItem::TraitMethod(trait_item, name) => {
+                tcx.lang_items().get(trait_item).is_some()
+                    && tcx.trait_of_item(def_id) == tcx.lang_items().get(trait_item)
+                    && tcx.opt_item_name(def_id) == Some(name)
+            },
+            SpecialItem::Method(self_ty, name) => {
+                tcx.opt_item_name(def_id) == Some(name)
+                    && method_self_adt(tcx, def_id, args).is_some_and(|adt| tcx.is_diagnostic_item(self_ty, adt))
+            },
+            SpecialItem::LangMethod(self_ty, name) => {
+                tcx.opt_item_name(def_id) == Some(name)
+                    && method_self_adt(tcx, def_id, args).is_some_and(|adt| tcx.lang_items().get(self_ty) == Some(adt))
+            },
+            SpecialItem::Path(path) => {
+                tcx.opt_item_name(def_id).is_some_and(|name| path.ends_with(name.as_str()))
+                    && with_no_trimmed_paths!(tcx.def_path_str(def_id)) == path
+            },
+        }
+    }
+}
+
+// look up the registry entry of a function, if it is a special one.
+pub fn special_fn<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId, args: GenericArgsRef<'tcx>) -> Option<&'static SpecialFn> {
+    SPECIAL_FNS.iter().find(|special| special.item.matches(tcx, def_id, args))
+}
+
+// Box::into_raw, std::mem::forget, and other functions that give up the ownership of their first argument.
+pub fn is_leak_fn<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId, args: GenericArgsRef<'tcx>) -> bool {
+    special_fn(tcx, def_id, args).map_or(false, |special| special.kind == SpecialFnKind::Leak)
+}
+
+// std::mem::drop, std::ptr::drop_in_place, and other functions that drop their first argument.
+pub fn is_drop_fn<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId, args: GenericArgsRef<'tcx>) -> bool {
+    special_fn(tcx, def_id, args).map_or(false, |special| special.kind == SpecialFnKind::Drop)
+}
 
 impl<'tcx> SafeDropGraph<'tcx> {
-    //can also use the format to check.
-    //these function calls are the functions whose MIRs can not be fetched.
+    //model a call of a special function, returns true if the call needs no further analysis.
+    //merge_vec holds the destination and then the arguments.
     pub fn corner_handle(
         &mut self,
-        _left_ssa: usize,
-        _merge_vec: &Vec<usize>,
+        left_ssa: usize,
+        merge_vec: &Vec<usize>,
         def_id: DefId,
+        args: GenericArgsRef<'tcx>,
     ) -> bool {
-        // CASE 1: function::call_mut
-        // #![feature(fn_traits)]
-        // fn main() {
-        //     let x = 1i32;
-        //     let mut c = || {x+1;};
-        //     c.call_mut(());
-        // }
-        if def_id.index.as_usize() == CALL_MUT {
-            return true;
-        }
-
-        // CASE 2: function::iterator::next
-        if def_id.index.as_usize() == NEXT {
-            return true;
+        match special_fn(self.tcx, def_id, args).map(|special| special.kind) {
+            Some(SpecialFnKind::NoEffect) => true,
+            Some(SpecialFnKind::Alias) => {
+                if merge_vec.len() > 1 && merge_vec[1] != 0 && self.values[merge_vec[1]].may_drop {
+                    self.merge_alias(left_ssa, merge_vec[1]);
+                }
+                true
+            },
+            // the effect of the call on the aliases is in its summary, the leak or duplication is recorded by the caller.
+            Some(SpecialFnKind::Drop) | Some(SpecialFnKind::Leak) | Some(SpecialFnKind::Duplicate) | Some(SpecialFnKind::Restore) | None => false,
         }
-
-        // CASE 3: intrinsic_offset
-        // For the latest rust version, this def_id of this function is removed. And we give the example below:
-        // #![feature(core_intrinsics)]
-        // use std::intrinsics::offset;
-        // fn main() {
-        //     unsafe {
-        //         let x = Box::new(1);
-        //         let mut ptr = &x as *const _;
-        //         ptr = offset(ptr, 1 as isize);
-        //     }
-        //
-        // }
-        //     bb1: {
-        //         _4 = &_1;
-        //         _3 = &raw const (*_4);
-        //         _2 = _3;
-        //         _6 = _2;
-        //         _7 = const 1_isize;
-        //         _5 = Offset(move _6, move _7);
-        //         _2 = move _5;
-        //         drop(_1) -> [return: bb2, unwind continue];
-        //     }
-        // if def_id.index.as_usize() == 1709 {
-        //     return true;
-        // }
-
-        return false;
     }
 
     //the dangling pointer occuring in some functions like drop() is reasonable.
-    pub fn should_check(def_id: DefId) -> bool {
+    //functions say so with #[rap::deallocator], the names are only a guess for the unannotated ones.
+    pub fn should_check(tcx: TyCtxt<'tcx>, def_id: DefId) -> bool {
+        let attrs = RapAttrs::of(tcx, def_id);
+        if attrs.annotated {
+            return !attrs.deallocator;
+        }
         let mut def_str = format!("{:?}", def_id);
         if let Some(x) = def_str.rfind("::") {
             def_str = def_str.get((x + "::".len())..).unwrap().to_string();
@@ -77,17 +336,3 @@ pub fn should_check(def_id: DefId) -> bool {
         return true;
     }
 }
-
-//these adt structs use the Rc-kind drop instruction, which we do not focus on.
-pub fn is_corner_adt(str: String) -> bool {
-    if let Some(_) = str.find("cell::RefMut") {
-        return true;
-    }
-    if let Some(_) = str.find("cell::Ref") {
-        return true;
-    }
-    if let Some(_) = str.find("rc::Rc") {
-        return true;
-    }
-    return false;
-}
@@ -0,0 +1,215 @@
+use rustc_data_structures::fx::FxHashMap;
+use rustc_middle::mir::{BasicBlock, Body, CallReturnPlaces, Location, Statement, Terminator, TerminatorEdges};
+use rustc_middle::ty::TyCtxt;
+use rustc_mir_dataflow::fmt::DebugWithContext;
+use rustc_mir_dataflow::{Analysis, AnalysisDomain, JoinSemiLattice};
+
+use super::bug_records::BugRecords;
+use super::graph::*;
+use super::safedrop::FuncMap;
+
+/*
+ * The birth used for drops in the dataflow mode.
+ * Statements are visited in order there, so a drop kills every value regardless of where it was born.
+ */
+pub const DATAFLOW_BIRTH: usize = isize::MAX as usize;
+
+/*
+ * The state of the dataflow mode at a program point: the alias sets and liveness of all values, and the known constants.
+ * Values created on different paths are matched by their local and field path when joining.
+ * The state of a block that is not reached yet is the bottom of the lattice, whatever its values.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafeDropState {
+    pub reachable: bool,
+    pub values: Vec<ValueNode>,
+    pub constant: FxHashMap<usize, usize>,
+}
+
+impl JoinSemiLattice for SafeDropState {
+    // a value may alias everything it aliases on either side, and it is dead if it is dead on either side.
+    fn join(&mut self, other: &Self) -> bool {
+        if !other.reachable {
+            return false;
+        }
+        if !self.reachable {
+            *self = other.clone();
+            return true;
+        }
+        let mut changed = false;
+        // map the values of `other` to the values of `self` with the same local and field path.
+        // the father of a value comes before it, so it is mapped first; pointees are fields too.
+        let mut map: Vec<usize> = Vec::with_capacity(other.values.len());
+        for (theirs, their_node) in other.values.iter().enumerate() {
+            if their_node.field_id == usize::MAX {
+                map.push(theirs);
+                continue;
+            }
+            let father = map[their_node.father];
+            let ours = match self.values[father].fields.get(&their_node.field_id) {
+                Some(&ours) => ours,
+                None => {
+                    let mut node = their_node.clone();
+                    node.index = self.values.len();
+                    node.alias = vec![node.index];
+                    node.fields = FxHashMap::default();
+                    node.father = father;
+                    self.values[father].fields.insert(node.field_id, node.index);
+                    self.values.push(node);
+                    changed = true;
+                    self.values.len() - 1
+                }
+            };
+            map.push(ours);
+        }
+
+        for (theirs, node) in other.values.iter().enumerate() {
+            let ours = map[theirs];
+            let aliases: Vec<usize> = node.alias.iter().map(|&alias| map[alias]).collect();
+            if self.values[ours].alias == [ours] {
+                // keep the head alias of the other side, which is what a deref resolves to.
+                if !aliases.is_empty() && aliases != [ours] {
+                    self.values[ours].alias = aliases;
+                    changed = true;
+                }
+            } else {
+                for alias in aliases {
+                    if !self.values[ours].alias.contains(&alias) {
+                        self.values[ours].alias.push(alias);
+                        changed = true;
+                    }
+                }
+            }
+            if !node.is_alive() && self.values[ours].is_alive() {
+                self.values[ours].birth = -1;
+                self.values[ours].drop_span = node.drop_span;
+                changed = true;
+            }
+            // a value is leaked if its ownership is given up on either side.
+            if node.leak_site.is_some() && self.values[ours].leak_site.is_none() {
+                self.values[ours].leak_site = node.leak_site;
+                changed = true;
+            }
+            if node.dup_site.is_some() && self.values[ours].dup_site.is_none() {
+                self.values[ours].dup_site = node.dup_site;
+                changed = true;
+            }
+            // memory initialized on one side only is initialized in part.
+            if node.init != self.values[ours].init && self.values[ours].init != InitState::PartiallyInit {
+                let site = self.values[ours].uninit_site.or(node.uninit_site);
+                self.values[ours].set_init(InitState::PartiallyInit, site);
+                changed = true;
+            }
+        }
+        // a constant is only known if it is the same on both sides.
+        let their_constants: FxHashMap<usize, usize> = other.constant.iter()
+            .filter(|(&theirs, _)| theirs < map.len())
+            .map(|(&theirs, &constant)| (map[theirs], constant))
+            .collect();
+        let constant_len = self.constant.len();
+        self.constant.retain(|value, constant| their_constants.get(value) == Some(constant));
+        changed |= self.constant.len() != constant_len;
+        changed
+    }
+}
+
+impl<C> DebugWithContext<C> for SafeDropState {}
+
+/*
+ * The dataflow mode of SafeDrop.
+ * Instead of enumerating paths, the effects of the blocks are joined at control-flow merges and iterated to a fixed point.
+ * The effect of a block is the same as in the path-sensitive mode: its assignments, calls and drops, in that order.
+ */
+pub struct SafeDropAnalysis<'a, 'tcx> {
+    pub tcx: TyCtxt<'tcx>,
+    pub graph: &'a mut SafeDropGraph<'tcx>,
+    pub func_map: &'a mut FuncMap<'tcx>,
+}
+
+impl<'a, 'tcx> SafeDropAnalysis<'a, 'tcx> {
+    // the graph holds the state of the block while its effect is applied; there is no single path to a block in this mode.
+    fn block_effect(&mut self, state: &mut SafeDropState, block: BasicBlock) {
+        if !state.reachable {
+            return;
+        }
+        std::mem::swap(&mut self.graph.values, &mut state.values);
+        std::mem::swap(&mut self.graph.constant, &mut state.constant);
+        self.graph.path = vec![block.as_usize()];
+        self.graph.block_effect(block.as_usize(), self.tcx, self.func_map);
+        std::mem::swap(&mut self.graph.values, &mut state.values);
+        std::mem::swap(&mut self.graph.constant, &mut state.constant);
+    }
+}
+
+impl<'a, 'tcx> AnalysisDomain<'tcx> for SafeDropAnalysis<'a, 'tcx> {
+    type Domain = SafeDropState;
+
+    const NAME: &'static str = "safedrop";
+
+    // the identity of `join`: joining it with any state gives that state.
+    fn bottom_value(&self, _body: &Body<'tcx>) -> Self::Domain {
+        SafeDropState { reachable: false, values: Vec::new(), constant: FxHashMap::default() }
+    }
+
+    fn initialize_start_block(&self, _body: &Body<'tcx>, state: &mut Self::Domain) {
+        state.reachable = true;
+        state.values = self.graph.values.clone();
+        state.constant = self.graph.constant.clone();
+    }
+}
+
+impl<'a, 'tcx> Analysis<'tcx> for SafeDropAnalysis<'a, 'tcx> {
+    fn apply_statement_effect(&mut self, _state: &mut Self::Domain, _statement: &Statement<'tcx>, _location: Location) {}
+
+    fn apply_terminator_effect<'mir>(
+        &mut self,
+        state: &mut Self::Domain,
+        terminator: &'mir Terminator<'tcx>,
+        location: Location,
+    ) -> TerminatorEdges<'mir, 'tcx> {
+        // the statements have no effect of their own, the whole block is applied here.
+        self.block_effect(state, location.block);
+        terminator.edges()
+    }
+
+    fn apply_call_return_effect(&mut self, _state: &mut Self::Domain, _block: BasicBlock, _return_places: CallReturnPlaces<'_, 'tcx>) {}
+}
+
+impl<'tcx> SafeDropGraph<'tcx> {
+    pub fn block_effect(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>, func_map: &mut FuncMap<'tcx>) {
+        self.alias_bb(bb_index, tcx);
+        self.uninit_bb(bb_index, tcx);
+        self.alias_bbcall(bb_index, tcx, func_map);
+        self.drop_check_with_birth(bb_index, DATAFLOW_BIRTH, tcx);
+    }
+
+    // the entry point of the dataflow mode, used instead of `solve_scc` and `check`.
+    pub fn check_dataflow(&mut self, body: &Body<'tcx>, tcx: TyCtxt<'tcx>, func_map: &mut FuncMap<'tcx>) {
+        let entry_states: Vec<SafeDropState> = {
+            let analysis = SafeDropAnalysis { tcx: tcx, graph: self, func_map: func_map };
+            let results = analysis.into_engine(tcx, body).iterate_to_fixpoint();
+            body.basic_blocks.indices().map(|block| results.entry_set_for_block(block).clone()).collect()
+        };
+        // bugs seen while iterating may come from states that are not final, so check again from the fixed point.
+        self.bug_records = BugRecords::new();
+        for (block, state) in entry_states.into_iter().enumerate() {
+            if !state.reachable {
+                continue;
+            }
+            self.values = state.values;
+            self.constant = state.constant;
+            // there is no single path to a block in this mode.
+            self.path = vec![block];
+            self.block_effect(block, tcx, func_map);
+            let cur_block = self.blocks[block].clone();
+            if cur_block.next.is_empty() {
+                if Self::should_check(self.tcx, self.def_id) {
+                    self.dp_check(&cur_block);
+                }
+                self.leak_check(&cur_block);
+                let results_nodes = self.values.clone();
+                self.merge_results(results_nodes, cur_block.is_cleanup);
+            }
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! Dumps of the SafeDrop graph of a body, requested like the MIR dumps, e.g.,
+//! `-Z dump-mir=SafeDrop`. The text form is written to `<crate>.<item>.SafeDrop.after.mir`
+//! in the MIR dump directory, so mir-opt tests can check it with `// EMIT_MIR`;
+//! `-Z dump-mir-graphviz` also writes the graph to a `.dot` file next to it.
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use rustc_graphviz as dot;
+use rustc_middle::mir::pretty::{create_dump_file, dump_enabled};
+use rustc_middle::mir::Body;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+
+use super::graph::{
+    InitState, SafeDropGraph, ANY_ELEMENT, DEREF_FIELD, ELEMENT_BASE, VARIANT_BASE,
+};
+
+const PASS_NAME: &str = "SafeDrop";
+
+/// Writes the SCC-condensed blocks of `graph` and the state of its values after the analysis,
+/// if the MIR dumps select the `SafeDrop` pass or the body.
+pub fn dump_safedrop_graph<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    graph: &SafeDropGraph<'tcx>,
+) {
+    if !dump_enabled(tcx, PASS_NAME, body.source.def_id()) {
+        return;
+    }
+    let _: io::Result<()> = try {
+        let mut file = create_dump_file(tcx, "mir", false, PASS_NAME, &"after", body)?;
+        write_safedrop_graph(graph, &mut file)?;
+    };
+    if tcx.sess.opts.unstable_opts.dump_mir_graphviz {
+        let _: io::Result<()> = try {
+            let mut file = create_dump_file(tcx, "dot", false, PASS_NAME, &"after", body)?;
+            let mut options = vec![dot::RenderOption::Fontname(
+                tcx.sess.opts.unstable_opts.graphviz_font.clone(),
+            )];
+            if tcx.sess.opts.unstable_opts.graphviz_dark_mode {
+                options.push(dot::RenderOption::DarkTheme);
+            }
+            dot::render_opts(&Graphviz(graph), &mut file, &options)?;
+        };
+    }
+}
+
+/// The textual form of the dump, one section per SCC node followed by the value tree.
+pub fn write_safedrop_graph(graph: &SafeDropGraph<'_>, w: &mut dyn Write) -> io::Result<()> {
+    let def_path = with_no_trimmed_paths!(graph.tcx.def_path_str(graph.def_id));
+    writeln!(w, "// SafeDrop graph for `{def_path}`")?;
+    for scc in scc_nodes(graph) {
+        writeln!(w)?;
+        writeln!(w, "{} {{", block_header(graph, scc))?;
+        for line in block_lines(graph, scc) {
+            writeln!(w, "    {line}")?;
+        }
+        writeln!(w, "}}")?;
+    }
+    writeln!(w)?;
+    writeln!(w, "values {{")?;
+    for line in value_lines(graph) {
+        writeln!(w, "    {line}")?;
+    }
+    writeln!(w, "}}")
+}
+
+//the blocks that stand for their scc, in the order of the CFG.
+fn scc_nodes(graph: &SafeDropGraph<'_>) -> Vec<usize> {
+    (0..graph.blocks.len()).filter(|block| graph.scc_indices[*block] == *block).collect()
+}
+
+fn successors(graph: &SafeDropGraph<'_>, scc: usize) -> Vec<usize> {
+    let mut next: Vec<usize> = graph.blocks[scc].next.iter().copied().collect();
+    next.sort_unstable();
+    next
+}
+
+fn block_list(blocks: &[usize]) -> String {
+    blocks.iter().map(|block| format!("bb{block}")).collect::<Vec<_>>().join(", ")
+}
+
+//e.g., `bb1 (scc: bb2, bb3) (cleanup) -> bb4`.
+fn block_header(graph: &SafeDropGraph<'_>, scc: usize) -> String {
+    let block = &graph.blocks[scc];
+    let mut header = format!("bb{scc}");
+    if !block.scc_sub_blocks.is_empty() {
+        header.push_str(&format!(" (scc: {})", block_list(&block.scc_sub_blocks)));
+    }
+    if block.is_cleanup {
+        header.push_str(" (cleanup)");
+    }
+    let next = successors(graph, scc);
+    if !next.is_empty() {
+        header.push_str(&format!(" -> {}", block_list(&next)));
+    }
+    header
+}
+
+//the assignments, calls and drops of an scc node; those of merged blocks are headed by the block.
+fn block_lines(graph: &SafeDropGraph<'_>, scc: usize) -> Vec<String> {
+    let mut members = vec![scc];
+    members.extend(graph.blocks[scc].scc_sub_blocks.iter().copied());
+    let merged = members.len() > 1;
+    let mut lines = Vec::new();
+    for member in members {
+        let block = &graph.blocks[member];
+        let indent = if merged {
+            lines.push(format!("bb{member}:"));
+            "    "
+        } else {
+            ""
+        };
+        for assign in block.assignments.iter() {
+            lines.push(format!("{indent}{:?} = {:?}({:?});", assign.lv, assign.atype, assign.rv));
+        }
+        for call in block.calls.iter() {
+            lines.push(format!("{indent}call {:?};", call.kind));
+        }
+        for drop in block.drops.iter() {
+            lines.push(format!("{indent}drop {:?};", drop.kind));
+        }
+    }
+    lines
+}
+
+fn field_name(key: usize) -> String {
+    match key {
+        DEREF_FIELD => "*".to_string(),
+        ANY_ELEMENT => "[_]".to_string(),
+        key if key >= ELEMENT_BASE => format!("[{}]", key - ELEMENT_BASE),
+        key if key >= VARIANT_BASE => format!(" as #{}", key - VARIANT_BASE),
+        key => format!(".{key}"),
+    }
+}
+
+//the value tree of every local, e.g., `#4 _1.*: Box, alias [#7], alive since 0`.
+fn value_lines(graph: &SafeDropGraph<'_>) -> Vec<String> {
+    let mut lines = Vec::new();
+    for local in 0..graph.local_tys.len().min(graph.values.len()) {
+        push_value_lines(graph, local, format!("_{local}"), 0, &mut lines);
+    }
+    lines
+}
+
+fn push_value_lines(
+    graph: &SafeDropGraph<'_>,
+    node: usize,
+    name: String,
+    depth: usize,
+    lines: &mut Vec<String>,
+) {
+    let value = &graph.values[node];
+    let aliases: Vec<String> = value
+        .alias
+        .iter()
+        .filter(|alias| **alias != node)
+        .map(|alias| format!("#{alias}"))
+        .collect();
+    let liveness =
+        if value.is_alive() { format!("alive since {}", value.birth) } else { "dead".to_string() };
+    let need_drop = if value.need_drop { ", needs drop" } else { "" };
+    let init = match value.init {
+        InitState::Init => "",
+        InitState::PartiallyInit => ", partially init",
+        InitState::Uninit => ", uninit",
+    };
+    lines.push(format!(
+        "{:indent$}#{node} {name}: {:?}{need_drop}{init}, alias [{}], {liveness}",
+        "",
+        value.kind,
+        aliases.join(", "),
+        indent = depth * 4,
+    ));
+    let mut fields: Vec<(usize, usize)> = value.fields.iter().map(|(k, v)| (*k, *v)).collect();
+    fields.sort_unstable();
+    for (key, field) in fields {
+        push_value_lines(graph, field, format!("{name}{}", field_name(key)), depth + 1, lines);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Node {
+    Block(usize),
+    Values,
+}
+
+struct Graphviz<'a, 'tcx>(&'a SafeDropGraph<'tcx>);
+
+//labels are left-justified lines, see `dot::LabelText::EscStr`.
+fn label(lines: impl IntoIterator<Item = String>) -> dot::LabelText<'static> {
+    let mut text = String::new();
+    for line in lines {
+        text.push_str(&line.replace('\\', "\\\\"));
+        text.push_str("\\l");
+    }
+    dot::LabelText::EscStr(text.into())
+}
+
+impl<'a, 'tcx> dot::Labeller<'a> for Graphviz<'a, 'tcx> {
+    type Node = Node;
+    type Edge = (usize, usize);
+
+    fn graph_id(&'a self) -> dot::Id<'a> {
+        dot::Id::new("safedrop").unwrap()
+    }
+
+    fn node_id(&'a self, n: &Node) -> dot::Id<'a> {
+        match *n {
+            Node::Block(scc) => dot::Id::new(format!("bb{scc}")).unwrap(),
+            Node::Values => dot::Id::new("values").unwrap(),
+        }
+    }
+
+    fn node_shape(&'a self, _n: &Node) -> Option<dot::LabelText<'a>> {
+        Some(dot::LabelText::label("box"))
+    }
+
+    fn node_label(&'a self, n: &Node) -> dot::LabelText<'a> {
+        match *n {
+            Node::Block(scc) => {
+                let header = block_header(self.0, scc);
+                label(std::iter::once(header).chain(block_lines(self.0, scc)))
+            }
+            Node::Values => label(std::iter::once("values".to_string()).chain(value_lines(self.0))),
+        }
+    }
+}
+
+impl<'a, 'tcx> dot::GraphWalk<'a> for Graphviz<'a, 'tcx> {
+    type Node = Node;
+    type Edge = (usize, usize);
+
+    fn nodes(&'a self) -> dot::Nodes<'a, Node> {
+        let mut nodes: Vec<Node> = scc_nodes(self.0).into_iter().map(Node::Block).collect();
+        nodes.push(Node::Values);
+        Cow::Owned(nodes)
+    }
+
+    fn edges(&'a self) -> dot::Edges<'a, (usize, usize)> {
+        let edges = scc_nodes(self.0)
+            .into_iter()
+            .flat_map(|scc| successors(self.0, scc).into_iter().map(move |next| (scc, next)))
+            .collect();
+        Cow::Owned(edges)
+    }
+
+    fn source(&'a self, edge: &(usize, usize)) -> Node {
+        Node::Block(edge.0)
+    }
+
+    fn target(&'a self, edge: &(usize, usize)) -> Node {
+        Node::Block(edge.1)
+    }
+}
@@ -13,12 +13,21 @@
 use rustc_data_structures::fx::FxHashMap;
 use rustc_middle::mir::Operand;
 use rustc_middle::mir::Rvalue;
+use rustc_middle::mir::AggregateKind;
+use rustc_middle::mir::ConstOperand;
+use rustc_middle::mir::BinOp;
+use rustc_middle::mir::NonDivergingIntrinsic;
 use rustc_middle::ty;
+use rustc_middle::ty::Ty;
+use rustc_middle::ty::GenericArgsRef;
+use rustc_middle::ty::adjustment::PointerCoercion;
+use rustc_middle::mir::CastKind;
+use rustc_target::abi::VariantIdx;
 use rustc_span::Span;
-use super::safedrop::*;
 use super::bug_records::*;
 use super::alias::*;
 use super::types::*;
+use super::corner_handle::*;
 
 #[derive(PartialEq,Debug,Copy,Clone)]
 pub enum AssignType {
@@ -26,6 +35,14 @@ pub enum AssignType {
     Move,
     InitBox,
     Variant,
+    // a copy of a value that is never dropped, only its discriminant or constant is tracked.
+    Value,
+    // a new value that aliases nothing, e.g., a reference to a thread local or a storage that becomes live.
+    Fresh,
+    // the discriminant of the place is set to the given value.
+    SetDiscriminant(usize),
+    // the storage of the place ends, pointers to it are dangling.
+    StorageDead,
 }
 
 //self-defined assignments structure. 
@@ -48,6 +65,14 @@ pub fn new(lv: Place<'tcx>, rv: Place<'tcx>, atype: AssignType, span: Span)->Ass
     }
 }
 
+//a read or a write of memory by a statement, for the initialization state of the values.
+#[derive(Debug,Clone)]
+pub struct Access<'tcx>{
+    pub place: Place<'tcx>,
+    pub is_write: bool,
+    pub span: Span,
+}
+
 /* 
  * Self-defined basicblock structure;
  * Used both for the original CFG and after SCC.
@@ -59,6 +84,8 @@ pub struct BlockNode<'tcx>{
     pub is_cleanup: bool,
     pub next: FxHashSet<usize>,
     pub assignments: Vec<Assignment<'tcx>>,
+    //the places read and written by the statements, in their order; only kept for the uninit checker.
+    pub accesses: Vec<Access<'tcx>>,
     pub calls: Vec<Terminator<'tcx>>,
     pub drops: Vec<Terminator<'tcx>>,
     //store the index of the basic blocks as a SCC node. 
@@ -67,6 +94,12 @@ pub struct BlockNode<'tcx>{
     pub const_value: Vec::<(usize, usize)>,
     //store switch stmts in current block for the path filtering in path-sensitive analysis.
     pub switch_stmts: Vec::<Terminator<'tcx>>,
+    //store the predecessors in the original CFG, the next blocks of a scc node are rewritten.
+    pub preds: Vec<usize>,
+    //the block ends the drop of a suspended generator, which returns no value.
+    pub is_generator_drop: bool,
+    //the block returns to the caller.
+    pub is_return: bool,
 }
 
 impl<'tcx> BlockNode<'tcx>{
@@ -76,11 +109,15 @@ pub fn new(index:usize, is_cleanup: bool) -> BlockNode<'tcx> {
             is_cleanup: is_cleanup,
             next: FxHashSet::<usize>::default(),
             assignments: Vec::<Assignment<'tcx>>::new(),
+            accesses: Vec::<Access<'tcx>>::new(),
             calls: Vec::<Terminator<'tcx>>::new(),
             drops: Vec::<Terminator<'tcx>>::new(),
             scc_sub_blocks: Vec::<usize>::new(),
             const_value: Vec::<(usize, usize)>::new(),
             switch_stmts: Vec::<Terminator<'tcx>>::new(),
+            preds: Vec::<usize>::new(),
+            is_generator_drop: false,
+            is_return: false,
         }
     }
 
@@ -89,17 +126,70 @@ pub fn add_next(&mut self, index: usize) {
     }
 }
 
-#[derive(Debug,Clone)]
+/*
+ * Besides field indices, the keys in `fields` name the other parts of a value, see `projection`.
+ * The variants of an enum have their own fields, and the elements of an array or a slice at
+ * constant indices are nodes of their own; elements at other indices share one node.
+ */
+// the node that a box or a pointer points to.
+pub const DEREF_FIELD: usize = usize::MAX - 1;
+// any element of an array or a slice.
+pub const ANY_ELEMENT: usize = usize::MAX - 2;
+pub const VARIANT_BASE: usize = usize::MAX / 2;
+pub const ELEMENT_BASE: usize = usize::MAX / 4 * 3;
+
+pub fn variant_key(variant: VariantIdx) -> usize {
+    VARIANT_BASE + variant.as_usize()
+}
+
+pub fn element_key(index: usize) -> usize {
+    match ELEMENT_BASE.checked_add(index) {
+        Some(key) if key < ANY_ELEMENT => key,
+        _ => ANY_ELEMENT,
+    }
+}
+
+// where a value got its aliases: an assignment, or a call and the callee whose summary was applied.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct AliasSite {
+    pub span: Span,
+    pub callee: Option<DefId>,
+}
+
+/*
+ * Whether the memory a node stands for is initialized. As for liveness, the state of a pointer
+ * is the state of what it points to, e.g., of the memory returned by `alloc`.
+ */
+#[derive(PartialEq,Eq,Debug,Copy,Clone)]
+pub enum InitState {
+    Init,
+    // initialized on some paths or in some parts only, e.g., a vector with spare capacity.
+    PartiallyInit,
+    Uninit,
+}
+
+#[derive(Debug,Clone,PartialEq,Eq)]
 pub struct ValueNode {
     pub index: usize, // node index
     pub local: usize, // location?
     pub need_drop: bool,
     pub may_drop: bool,
     pub kind: TyKind,
-    pub father: usize,
+    pub father: usize, // the node it is a field of, or its local.
     pub field_id: usize, // the field id of its father node.
     pub alias: Vec<usize>,
     pub birth: isize,
+    // the drop that killed the node, used to point at the free site in bug reports.
+    pub drop_span: Option<Span>,
+    // the last assignment or call that gave the node its aliases, used in the traces of bug reports.
+    pub alias_site: Option<AliasSite>,
+    // the call that gave up the ownership of the node without freeing it, e.g., Box::into_raw.
+    pub leak_site: Option<Span>,
+    // the call that duplicated the ownership of what the node points to bit-wise, e.g., ptr::read.
+    pub dup_site: Option<Span>,
+    pub init: InitState,
+    // where the memory that is not initialized yet was created, e.g., by MaybeUninit::uninit.
+    pub uninit_site: Option<Span>,
     pub fields: FxHashMap<usize, usize>,
 }
 
@@ -113,20 +203,32 @@ pub fn new(index: usize, local: usize, need_drop: bool, may_drop: bool) -> Self
             field_id: usize::MAX, 
             alias: vec![index], 
             birth: 0, 
+            drop_span: None, 
+            alias_site: None, 
+            leak_site: None, 
+            dup_site: None, 
+            init: InitState::Init, 
+            uninit_site: None, 
             may_drop: may_drop, 
             kind: TyKind::Adt, 
             fields: FxHashMap::default(), 
         }
     }
 
-    pub fn dead(&mut self) { 
+    pub fn dead(&mut self, span: Span) { 
         self.birth = -1; 
+        self.drop_span = Some(span); 
     }
 
     pub fn is_alive(&self) -> bool { 
         self.birth > -1 
     }
 
+    pub fn set_init(&mut self, init: InitState, site: Option<Span>) {
+        self.init = init;
+        self.uninit_site = site;
+    }
+
     pub fn is_tuple(&self)-> bool { 
         self.kind == TyKind::Tuple 
     }
@@ -139,17 +241,26 @@ pub fn is_ref(&self)-> bool {
         self.kind == TyKind::Ref 
     }
 
-    pub fn is_corner_case(&self)-> bool { 
-        self.kind == TyKind::CornerCase 
+    pub fn is_box(&self)-> bool { 
+        self.kind == TyKind::Box 
+    }
+
+    // weak pointers and RefCell guards do not own what they point to.
+    pub fn frees_on_drop(&self)-> bool { 
+        self.kind != TyKind::WeakPtr && self.kind != TyKind::CellGuard 
     }
 }
 
 pub struct SafeDropGraph<'tcx>{
     pub def_id: DefId,
     pub tcx: TyCtxt<'tcx>,
+    // the environment the types of the body are checked in, revealing all for monomorphized instances.
+    pub param_env: ty::ParamEnv<'tcx>,
     pub span: Span,
     // contains all varibles (including fields) as values.
     pub values: Vec<ValueNode>,
+    // the types of the locals, used to type the nodes created by projections.
+    pub local_tys: Vec<Ty<'tcx>>,
     // contains all blocks in the CFG
     pub blocks: Vec<BlockNode<'tcx>>,
     pub arg_size: usize, 
@@ -165,20 +276,131 @@ pub struct SafeDropGraph<'tcx>{
     pub bug_records: BugRecords,
     // a threhold to avoid path explosion.
     pub visit_times: usize,
+    // the iteration of the scc being unrolled, 0 outside of loops.
+    pub loop_round: usize,
+    // the functions that may be stored in the function pointer locals.
+    pub fn_ptrs: FxHashMap<usize, Vec<(DefId, GenericArgsRef<'tcx>)>>,
+    // the blocks of the path being checked, from the entry; scc nodes are recorded once.
+    pub path: Vec<usize>,
+}
+
+// the discriminant of a constant enum value.
+fn constant_discr<'tcx>(tcx: TyCtxt<'tcx>, param_env: ty::ParamEnv<'tcx>, constant: &ConstOperand<'tcx>) -> Option<usize> {
+    let ty = constant.ty();
+    if !ty.is_enum() {
+        return None;
+    }
+    let value = constant.const_.eval(tcx, param_env, None).ok()?;
+    let variant = tcx.try_destructure_mir_constant_for_diagnostics(value, ty)?.variant?;
+    Some(ty.discriminant_for_variant(tcx, variant)?.val as usize)
+}
+
+/*
+ * The functions stored in the function pointer locals of a body, e.g., `_2 = foo as fn() (PointerCoercion(ReifyFnPointer))`.
+ * It is not flow-sensitive: a local holds every function it is assigned in the body, directly or from another local.
+ */
+pub fn fn_ptr_targets<'tcx>(body: &Body<'tcx>) -> FxHashMap<usize, Vec<(DefId, GenericArgsRef<'tcx>)>> {
+    let mut targets = FxHashMap::<usize, Vec<(DefId, GenericArgsRef<'tcx>)>>::default();
+    let mut copies = Vec::new();
+    for block in body.basic_blocks.iter() {
+        for stmt in &block.statements {
+            if let StatementKind::Assign(box (ref lv, ref rvalue)) = stmt.kind && lv.projection.is_empty() {
+                match rvalue {
+                    Rvalue::Cast(CastKind::PointerCoercion(PointerCoercion::ReifyFnPointer), Operand::Constant(ref c), _) => {
+                        if let ty::FnDef(id, args) = *c.ty().kind() {
+                            targets.entry(lv.local.as_usize()).or_default().push((id, args));
+                        }
+                    },
+                    Rvalue::Use(Operand::Copy(ref p) | Operand::Move(ref p)) if p.projection.is_empty() && body.local_decls[p.local].ty.is_fn_ptr() => {
+                        copies.push((lv.local.as_usize(), p.local.as_usize()));
+                    },
+                    _ => {}
+                }
+            }
+        }
+    }
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (lv, rv) in copies.iter() {
+            for target in targets.get(rv).cloned().unwrap_or_default() {
+                let lv_targets = targets.entry(*lv).or_default();
+                if !lv_targets.contains(&target) {
+                    lv_targets.push(target);
+                    changed = true;
+                }
+            }
+        }
+    }
+    targets
+}
+
+// the places a statement reads, and then the place it writes.
+// a value moved or copied to one that aliases it is not read, its memory is read when it is used.
+pub fn statement_accesses<'tcx>(tcx: TyCtxt<'tcx>, values: &Vec<ValueNode>, kind: &StatementKind<'tcx>, span: Span) -> Vec<Access<'tcx>> {
+    let mut reads = Vec::new();
+    let mut write = None;
+    match kind {
+        StatementKind::Assign(box (lv, rvalue)) => {
+            match rvalue {
+                Rvalue::Use(x) => {
+                    if let Some(p) = x.place() && !(values[lv.local.as_usize()].may_drop && values[p.local.as_usize()].may_drop) {
+                        reads.push(p);
+                    }
+                },
+                Rvalue::Repeat(x, _) | Rvalue::Cast(_, x, _) | Rvalue::UnaryOp(_, x) | Rvalue::ShallowInitBox(x, _) => {
+                    reads.extend(x.place());
+                },
+                Rvalue::BinaryOp(_, box (x, y)) | Rvalue::CheckedBinaryOp(_, box (x, y)) => {
+                    reads.extend(x.place());
+                    reads.extend(y.place());
+                },
+                Rvalue::Aggregate(_, xs) => reads.extend(xs.iter().filter_map(|x| x.place())),
+                Rvalue::CopyForDeref(p) | Rvalue::Discriminant(p) | Rvalue::Len(p) => reads.push(*p),
+                // taking a reference or an address reads nothing.
+                _ => {},
+            }
+            write = Some(*lv);
+        },
+        StatementKind::Intrinsic(box NonDivergingIntrinsic::CopyNonOverlapping(copy)) => {
+            if let (Some(src), Some(dst)) = (copy.src.place(), copy.dst.place()) {
+                reads.push(tcx.mk_place_deref(src));
+                write = Some(tcx.mk_place_deref(dst));
+            }
+        },
+        _ => {},
+    }
+    let mut accesses: Vec<Access<'tcx>> = reads.into_iter().map(|place| Access { place: place, is_write: false, span: span }).collect();
+    accesses.extend(write.map(|place| Access { place: place, is_write: true, span: span }));
+    accesses
+}
+
+// the functions a call may run before trait methods are resolved: the called function, or those stored in the called pointer.
+pub fn call_targets<'tcx>(func: &Operand<'tcx>, fn_ptrs: &FxHashMap<usize, Vec<(DefId, GenericArgsRef<'tcx>)>>) -> Vec<(DefId, GenericArgsRef<'tcx>)> {
+    match func {
+        Operand::Constant(ref c) => match *c.ty().kind() {
+            ty::FnDef(id, args) => vec![(id, args)],
+            _ => Vec::new(),
+        },
+        Operand::Copy(ref p) | Operand::Move(ref p) if p.projection.is_empty() => {
+            fn_ptrs.get(&p.local.as_usize()).cloned().unwrap_or_default()
+        },
+        _ => Vec::new(),
+    }
 }
 
 impl<'tcx> SafeDropGraph<'tcx> {
-    pub fn new(body: &Body<'tcx>,  tcx: TyCtxt<'tcx>, def_id: DefId) -> SafeDropGraph<'tcx> {  
+    pub fn new(body: &Body<'tcx>,  tcx: TyCtxt<'tcx>, def_id: DefId, param_env: ty::ParamEnv<'tcx>) -> SafeDropGraph<'tcx> {  
         // handle variables
         let locals = &body.local_decls;
         let arg_size = body.arg_count;
         let mut values = Vec::<ValueNode>::new();
-        let param_env = tcx.param_env(def_id);
+        let record_accesses = tcx.sess.opts.unstable_opts.safedrop.map_or(true, |checkers| checkers.uninit_memory);
         for (local, local_decl) in locals.iter_enumerated() {
             let need_drop = local_decl.ty.needs_drop(tcx, param_env); // the type is drop
             let may_drop = !is_not_drop(tcx, local_decl.ty);
             let mut node = ValueNode::new(local.as_usize(), local.as_usize(), need_drop, need_drop || may_drop);
-            node.kind = kind(local_decl.ty);
+            node.kind = kind(tcx, local_decl.ty);
             values.push(node);
         }
         
@@ -197,6 +419,9 @@ pub fn new(body: &Body<'tcx>,  tcx: TyCtxt<'tcx>, def_id: DefId) -> SafeDropGrap
             for stmt in &basicblocks[iter].statements {
 		        /* Assign is a tuple defined as Assign(Box<(Place<'tcx>, Rvalue<'tcx>)>) */
                 let span = stmt.source_info.span.clone();
+                if record_accesses {
+                    cur_bb.accesses.extend(statement_accesses(tcx, &values, &stmt.kind, span));
+                }
                 if let StatementKind::Assign(ref assign) = stmt.kind {
                     let lv_local = assign.0.local.as_usize(); // assign.0 is a Place
                     let lv = assign.0.clone();
@@ -209,6 +434,10 @@ pub fn new(body: &Body<'tcx>,  tcx: TyCtxt<'tcx>, def_id: DefId) -> SafeDropGrap
                                         let rv = p.clone();
                                         let assign = Assignment::new(lv, rv, AssignType::Copy, span);
                                         cur_bb.assignments.push(assign);
+                                    } else if !values[lv_local].may_drop && !values[rv_local].may_drop {
+                                        let rv = p.clone();
+                                        let assign = Assignment::new(lv, rv, AssignType::Value, span);
+                                        cur_bb.assignments.push(assign);
                                     }
                                 },
                                 Operand::Move(ref p) => {
@@ -217,6 +446,10 @@ pub fn new(body: &Body<'tcx>,  tcx: TyCtxt<'tcx>, def_id: DefId) -> SafeDropGrap
                                         let rv = p.clone();
                                         let assign = Assignment::new(lv, rv, AssignType::Move, span);
                                         cur_bb.assignments.push(assign);
+                                    } else if !values[lv_local].may_drop && !values[rv_local].may_drop {
+                                        let rv = p.clone();
+                                        let assign = Assignment::new(lv, rv, AssignType::Value, span);
+                                        cur_bb.assignments.push(assign);
                                     }
                                 },
                                 Operand::Constant(ref constant) => { 
@@ -224,13 +457,18 @@ pub fn new(body: &Body<'tcx>,  tcx: TyCtxt<'tcx>, def_id: DefId) -> SafeDropGrap
                                         cur_bb.const_value.push((lv_local, val as usize));
                                     } else if let Some(const_bool) = constant.const_.try_to_bool() {
                                         cur_bb.const_value.push((lv_local, const_bool as usize));
+                                    } else if let Some(discr) = constant_discr(tcx, param_env, constant) {
+                                        // e.g., `_1 = const Option::<T>::None`
+                                        if lv.projection.is_empty() {
+                                            cur_bb.const_value.push((lv_local, discr));
+                                        }
                                     }
                                 },
                             }
                         }
                         Rvalue::Ref(_, _, ref p) | Rvalue::AddressOf(_, ref p) => {
-                            let rv_local = p.local.as_usize();
-                            if values[lv_local].may_drop && values[rv_local].may_drop {
+                            // the referenced value need not be dropped, its storage can still end under the reference.
+                            if values[lv_local].may_drop {
                                 let rv = p.clone();
                                 let assign = Assignment::new(lv, rv, AssignType::Copy, span);
                                 cur_bb.assignments.push(assign);
@@ -239,12 +477,13 @@ pub fn new(body: &Body<'tcx>,  tcx: TyCtxt<'tcx>, def_id: DefId) -> SafeDropGrap
                         Rvalue::ShallowInitBox(ref x, _) => {
                             /* 
                              * Original ShllowInitBox is a two-level pointer: lvl0 -> lvl1 -> lvl2 
-                             * Since our alias analysis does not consider multi-level pointer,
-                             * We simplify it as: lvl0
+                             * We keep the allocation as lvl0, i.e., field 0 of the box;
+                             * the contents written through `*box` are the DEREF_FIELD node of the box.
                              */
                             if !values[lv_local].fields.contains_key(&0) {
                                 let mut lvl0 = ValueNode::new(values.len(), lv_local, false, true);
                                 lvl0.birth = values[lv_local].birth;
+                                lvl0.father = lv_local;
                                 lvl0.field_id = 0;
                                 values[lv_local].fields.insert(0, lvl0.index);
                                 values.push(lvl0);
@@ -282,13 +521,25 @@ pub fn new(body: &Body<'tcx>,  tcx: TyCtxt<'tcx>, def_id: DefId) -> SafeDropGrap
                                 Operand::Constant(_) => {},
                             }
                         },
-                        Rvalue::Aggregate(_, ref x) => {
-                            for each_x in x {
+                        Rvalue::Aggregate(ref kind, ref x) => {
+                            // the discriminant of an enum built here is known, e.g., `_1 = Option::<T>::Some(move _2)`
+                            if let AggregateKind::Adt(_, variant, ..) = **kind && lv.projection.is_empty() {
+                                let ty = lv.ty(&body.local_decls, tcx).ty;
+                                if let Some(discr) = ty.discriminant_for_variant(tcx, variant) {
+                                    cur_bb.const_value.push((lv_local, discr.val as usize));
+                                }
+                            }
+                            for (i, each_x) in x.iter_enumerated() {
                                 match each_x {
                                     Operand::Copy(ref p) | Operand::Move(ref p) => {
                                         let rv_local = p.local.as_usize();
                                         if values[lv_local].may_drop && values[rv_local].may_drop {
                                             let rv = p.clone();
+                                            // the elements of a tuple are its fields, e.g., the arguments of a closure call.
+                                            let lv = match **kind {
+                                                AggregateKind::Tuple => tcx.mk_place_field(lv, i, each_x.ty(&body.local_decls, tcx)),
+                                                _ => lv,
+                                            };
                                             let assign = Assignment::new(lv, rv, AssignType::Copy, span);
                                             cur_bb.assignments.push(assign);
                                         }
@@ -302,9 +553,70 @@ pub fn new(body: &Body<'tcx>,  tcx: TyCtxt<'tcx>, def_id: DefId) -> SafeDropGrap
                             let assign = Assignment::new(lv, rv, AssignType::Variant, span);
                             cur_bb.assignments.push(assign);
                         }
+                        Rvalue::CopyForDeref(ref p) => {
+                            // a copy of the place a deref is taken from.
+                            let rv_local = p.local.as_usize();
+                            if values[lv_local].may_drop && values[rv_local].may_drop {
+                                let rv = p.clone();
+                                let assign = Assignment::new(lv, rv, AssignType::Copy, span);
+                                cur_bb.assignments.push(assign);
+                            }
+                        },
+                        Rvalue::BinaryOp(BinOp::Offset, box (ref x, _)) | Rvalue::Repeat(ref x, _) => {
+                            // an offset pointer points into the same allocation, and an array repeats its element.
+                            match x {
+                                Operand::Copy(ref p) | Operand::Move(ref p) => {
+                                    let rv_local = p.local.as_usize();
+                                    if values[lv_local].may_drop && values[rv_local].may_drop {
+                                        let rv = p.clone();
+                                        let assign = Assignment::new(lv, rv, AssignType::Copy, span);
+                                        cur_bb.assignments.push(assign);
+                                    }
+                                },
+                                Operand::Constant(_) => {},
+                            }
+                        },
+                        Rvalue::ThreadLocalRef(_) => {
+                            // thread locals are not dropped in the body.
+                            let assign = Assignment::new(lv, lv, AssignType::Fresh, span);
+                            cur_bb.assignments.push(assign);
+                        },
                         _ => {}
                     }
                 }
+                match stmt.kind {
+                    StatementKind::SetDiscriminant { ref place, variant_index } => {
+                        let ty = place.ty(&body.local_decls, tcx).ty;
+                        if let Some(discr) = ty.discriminant_for_variant(tcx, variant_index) {
+                            let assign = Assignment::new(**place, **place, AssignType::SetDiscriminant(discr.val as usize), span);
+                            cur_bb.assignments.push(assign);
+                        }
+                    },
+                    StatementKind::Deinit(ref place) => {
+                        let assign = Assignment::new(**place, **place, AssignType::Fresh, span);
+                        cur_bb.assignments.push(assign);
+                    },
+                    StatementKind::StorageLive(local) => {
+                        let assign = Assignment::new(Place::from(local), Place::from(local), AssignType::Fresh, span);
+                        cur_bb.assignments.push(assign);
+                    },
+                    StatementKind::StorageDead(local) => {
+                        let assign = Assignment::new(Place::from(local), Place::from(local), AssignType::StorageDead, span);
+                        cur_bb.assignments.push(assign);
+                    },
+                    StatementKind::Intrinsic(box NonDivergingIntrinsic::CopyNonOverlapping(ref copy)) => {
+                        // `*dst = *src`, for as many elements as `count`.
+                        if let (Operand::Copy(src) | Operand::Move(src), Operand::Copy(dst) | Operand::Move(dst)) = (&copy.src, &copy.dst) {
+                            if values[src.local.as_usize()].may_drop && values[dst.local.as_usize()].may_drop {
+                                let lv = tcx.mk_place_deref(*dst);
+                                let rv = tcx.mk_place_deref(*src);
+                                let assign = Assignment::new(lv, rv, AssignType::Copy, span);
+                                cur_bb.assignments.push(assign);
+                            }
+                        }
+                    },
+                    _ => {}
+                }
             }
 
             // handle terminator statements
@@ -319,11 +631,15 @@ pub fn new(body: &Body<'tcx>,  tcx: TyCtxt<'tcx>, def_id: DefId) -> SafeDropGrap
                     }
                     cur_bb.add_next(targets.otherwise().as_usize());
                 }, 
+                TerminatorKind::Return => {
+                    cur_bb.is_return = true;
+                },
                 TerminatorKind::UnwindResume
-                | TerminatorKind::Return
                 | TerminatorKind::UnwindTerminate(_)
-                | TerminatorKind::GeneratorDrop
                 | TerminatorKind::Unreachable => {},
+                TerminatorKind::GeneratorDrop => {
+                    cur_bb.is_generator_drop = true;
+                },
                 TerminatorKind::Drop { place: _, ref target, ref unwind , replace: _} => {
                     cur_bb.add_next(target.as_usize());
                     cur_bb.drops.push(terminator.clone());
@@ -335,9 +651,9 @@ pub fn new(body: &Body<'tcx>,  tcx: TyCtxt<'tcx>, def_id: DefId) -> SafeDropGrap
                     match func {
                         Operand::Constant(c) => {
                             match c.ty().kind() {
-                                ty::FnDef(id, ..) => {
-                                    // DROP(1634) means std::mem::drop, 2160 means std::ptr::drop_in_place
-                                    if id.index.as_usize() == DROP || id.index.as_usize() == DROP_IN_PLACE {
+                                ty::FnDef(id, args) => {
+                                    // e.g., std::mem::drop and std::ptr::drop_in_place
+                                    if is_drop_fn(tcx, *id, args) {
                                         cur_bb.drops.push(terminator.clone());
                                     }
                                 }
@@ -366,6 +682,8 @@ pub fn new(body: &Body<'tcx>,  tcx: TyCtxt<'tcx>, def_id: DefId) -> SafeDropGrap
                     if let Some(target) = drop {
                         cur_bb.add_next(target.as_usize());
                     }
+                    // the yielded value and the resume argument are handled like the arguments and the destination of a call.
+                    cur_bb.calls.push(terminator.clone());
                 },
                 TerminatorKind::FalseEdge { ref real_target, imaginary_target: _ } => {
                     cur_bb.add_next(real_target.as_usize());
@@ -385,12 +703,20 @@ pub fn new(body: &Body<'tcx>,  tcx: TyCtxt<'tcx>, def_id: DefId) -> SafeDropGrap
             blocks.push(cur_bb);
         }
 
+        for i in 0..blocks.len() {
+            for j in blocks[i].next.clone() {
+                blocks[j].preds.push(i);
+            }
+        }
+
         SafeDropGraph{
             def_id: def_id.clone(),
             tcx: tcx,
+            param_env: param_env,
             span: body.span,
             blocks: blocks,
             values: values,
+            local_tys: locals.iter().map(|local_decl| local_decl.ty).collect(),
             arg_size: arg_size,
             scc_indices: scc_indices,
             constant: FxHashMap::default(), 
@@ -398,6 +724,9 @@ pub fn new(body: &Body<'tcx>,  tcx: TyCtxt<'tcx>, def_id: DefId) -> SafeDropGrap
             return_set: FxHashSet::default(),
             bug_records: BugRecords::new(),
             visit_times: 0,
+            loop_round: 0,
+            fn_ptrs: fn_ptr_targets(body),
+            path: Vec::new(),
         }
     }
 
@@ -453,6 +782,11 @@ pub fn tarjan(&mut self, index: usize, stack: &mut Vec<usize>, instack: &mut FxH
         }
     }
 
+    // the birth of the values assigned in a block; every round of an unrolled scc is born after the previous one.
+    pub fn birth_of(&self, bb_index: usize) -> usize {
+        self.scc_indices[bb_index] + self.loop_round * self.blocks.len()
+    }
+
     // handle SCC
     pub fn solve_scc(&mut self) {
         let mut stack = Vec::<usize>::new();
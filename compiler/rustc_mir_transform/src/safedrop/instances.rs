@@ -0,0 +1,111 @@
+//! SafeDrop on monomorphized instances (`-Z safedrop-instances`). The bodies of the local
+//! functions are analysed once per instance that code generation uses, with their type
+//! parameters substituted, so that, e.g., a `T` that is `Copy` in one instance and needs drop
+//! in another is told apart. The bugs of all instances of a function are reported once, at
+//! the generic function; functions without instances are analysed generically, and so is
+//! every function in builds that do not generate code, which collect no instances.
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::{self, Instance, InstanceDef, ParamEnv, TyCtxt};
+use rustc_span::def_id::LocalDefId;
+
+use super::bug_records::BugRecords;
+use super::check_bugs::report_bug_records;
+use super::report::Finding;
+use super::safedrop::{analyze_body_in, FuncMap, VISIT_LIMIT};
+use crate::{rap_info, record_msg, RapLogLevel, RAP_LOGGER};
+use log::Log;
+
+/// The most instances of one function that are analysed; the others are left out with a note.
+pub const INSTANCE_LIMIT: usize = 16;
+
+/// Checks every local body, through its instances if it has any, and returns the findings of
+/// all of them.
+pub fn check_instances(tcx: TyCtxt<'_>) -> Vec<Finding> {
+    tcx.hir().par_body_owners(|def_id| {
+        if tcx.safedrop_mono_instances(()).contains_key(&def_id) {
+            tcx.ensure().safedrop_check_instances(def_id);
+        } else {
+            tcx.ensure().query_safedrop(def_id.to_def_id());
+        }
+    });
+    let instances_of = tcx.safedrop_mono_instances(());
+    tcx.hir()
+        .body_owners()
+        .flat_map(|def_id| {
+            if instances_of.contains_key(&def_id) {
+                tcx.safedrop_check_instances(def_id).iter().cloned()
+            } else {
+                tcx.query_safedrop(def_id.to_def_id()).iter().cloned()
+            }
+        })
+        .collect()
+}
+
+/// The instances of the local functions in the codegen units, which are partitioned for code
+/// generation anyway, so the items are not collected a second time.
+pub fn safedrop_mono_instances<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    (): (),
+) -> FxIndexMap<LocalDefId, Vec<Instance<'tcx>>> {
+    let mut instances_of: FxIndexMap<LocalDefId, Vec<Instance<'tcx>>> = FxIndexMap::default();
+    if !tcx.sess.opts.output_types.should_codegen() {
+        return instances_of;
+    }
+    let (_, cgus) = tcx.collect_and_partition_mono_items(());
+    for cgu in cgus {
+        for item in cgu.items().keys() {
+            if let MonoItem::Fn(instance) = *item
+                && let InstanceDef::Item(def_id) = instance.def
+                && let Some(local) = def_id.as_local()
+            {
+                instances_of.entry(local).or_default().push(instance);
+            }
+        }
+    }
+    // The codegen units are hash maps, and inlined items are in several of them.
+    for instances in instances_of.values_mut() {
+        instances.sort_by_cached_key(|instance| instance.to_string());
+        instances.dedup();
+    }
+    instances_of.sort_by(|a, _, b, _| a.local_def_index.cmp(&b.local_def_index));
+    instances_of
+}
+
+pub fn safedrop_check_instances(tcx: TyCtxt<'_>, def_id: LocalDefId) -> Vec<Finding> {
+    let instances = &tcx.safedrop_mono_instances(())[&def_id];
+    check_def(tcx, def_id, instances)
+}
+
+fn check_def<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: LocalDefId,
+    instances: &[Instance<'tcx>],
+) -> Vec<Finding> {
+    /* filter const mir */
+    if tcx.hir().body_const_context(def_id).is_some() || !tcx.is_mir_available(def_id) {
+        return Vec::new();
+    }
+    let generic_body = tcx.safedrop_mir(def_id);
+    if instances.len() > INSTANCE_LIMIT {
+        rap_info!("Instances analysed: {} of {} for {:?}", INSTANCE_LIMIT, instances.len(), def_id);
+    }
+    let mut bug_records = BugRecords::new();
+    for instance in instances.iter().take(INSTANCE_LIMIT) {
+        let body = instance.instantiate_mir_and_normalize_erasing_regions(
+            tcx,
+            ParamEnv::reveal_all(),
+            ty::EarlyBinder::bind(generic_body.clone()),
+        );
+        let mut func_map = FuncMap::new();
+        let safedrop_graph =
+            analyze_body_in(tcx, &body, def_id.to_def_id(), ParamEnv::reveal_all(), &mut func_map);
+        if safedrop_graph.visit_times > VISIT_LIMIT {
+            rap_info!("Over visited: {}", instance);
+            continue;
+        }
+        bug_records.merge(safedrop_graph.bug_records);
+    }
+    report_bug_records(tcx, def_id.to_def_id(), &bug_records)
+}
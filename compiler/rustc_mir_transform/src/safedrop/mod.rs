@@ -1,9 +1,17 @@
 pub mod safedrop;
+pub mod instances;
 pub mod graph;
 pub mod bug_records;
+pub mod report;
+pub mod spec;
+pub mod dump;
 pub mod check_bugs;
+pub mod dataflow;
 pub mod corner_handle;
 pub mod types;
+pub mod uninit;
 pub mod alias;
+pub mod attrs;
+pub mod summary;
 pub mod log;
 pub mod utils;
@@ -0,0 +1,241 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use rustc_hir::HirId;
+pub use rustc_middle::mir::safedrop::{AliasRecord, BugKind, Finding, SpanRange, TraceRecord};
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::SafeDropReportFormat;
+use rustc_session::lint::Level;
+use rustc_span::def_id::DefId;
+use serde_json::json;
+
+use super::bug_records::{BugSite, BugTrace};
+use crate::errors;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+fn trace_record(tcx: TyCtxt<'_>, trace: &BugTrace) -> TraceRecord {
+    let path = trace.path.iter().map(|block| format!("bb{block}")).collect();
+    let aliases = trace
+        .alias_sites
+        .iter()
+        .map(|site| AliasRecord {
+            span: SpanRange::new(tcx, site.span),
+            callee: site.callee.map(|callee| with_no_trimmed_paths!(tcx.def_path_str(callee))),
+        })
+        .collect();
+    TraceRecord { path, aliases }
+}
+
+//the finding of a bug for the report, unless the lint is allowed at `hir_id`. findings are
+//recorded whether or not a report was requested, as they are cached with `query_safedrop`.
+pub fn record_finding(
+    tcx: TyCtxt<'_>,
+    def_id: DefId,
+    hir_id: HirId,
+    kind: BugKind,
+    site: &BugSite,
+    unwind: bool,
+) -> Option<Finding> {
+    let level = match tcx.lint_level_at_node(kind.lint(), hir_id).0 {
+        Level::Allow | Level::Expect(_) => return None,
+        Level::Warn | Level::ForceWarn(_) => "warning",
+        Level::Deny | Level::Forbid => "error",
+    };
+    Some(Finding {
+        kind: kind,
+        level: level,
+        function: with_no_trimmed_paths!(tcx.def_path_str(def_id)),
+        span: SpanRange::new(tcx, site.span),
+        unwind: unwind,
+        drop_site: site.drop_span.map(|span| SpanRange::new(tcx, span)),
+        trace: trace_record(tcx, &site.trace),
+    })
+}
+
+//write the findings of the crate to the path given by `-Z safedrop-report`.
+pub fn write_report(tcx: TyCtxt<'_>, mut findings: Vec<Finding>) {
+    let Some(ref path) = tcx.sess.opts.unstable_opts.safedrop_report else {
+        return;
+    };
+    //sort to keep the report independent of the order the bodies are checked in.
+    findings.sort_by(|a, b| {
+        (&a.span.file, a.span.start_line, a.span.start_column, a.kind.name()).cmp(&(
+            &b.span.file,
+            b.span.start_line,
+            b.span.start_column,
+            b.kind.name(),
+        ))
+    });
+    let format = tcx.sess.opts.unstable_opts.safedrop_report_format;
+    if let Err(err) = write_findings(path, format, &findings) {
+        tcx.sess.emit_err(errors::SafeDropReportError {
+            path: path.display().to_string(),
+            error: err.to_string(),
+        });
+    }
+}
+
+fn write_findings(
+    path: &Path,
+    format: SafeDropReportFormat,
+    findings: &[Finding],
+) -> Result<(), Box<dyn Error>> {
+    let mut file = BufWriter::new(File::create(path)?);
+    match format {
+        SafeDropReportFormat::Json => {
+            let findings: Vec<_> = findings.iter().map(json_finding).collect();
+            serde_json::to_writer_pretty(&mut file, &findings)?
+        }
+        SafeDropReportFormat::Sarif => {
+            serde_json::to_writer_pretty(&mut file, &sarif_log(findings))?
+        }
+    }
+    writeln!(file)?;
+    file.flush()?;
+    Ok(())
+}
+
+fn json_range(range: &SpanRange) -> serde_json::Value {
+    json!({
+        "file": range.file,
+        "start_line": range.start_line,
+        "start_column": range.start_column,
+        "end_line": range.end_line,
+        "end_column": range.end_column,
+    })
+}
+
+fn json_finding(finding: &Finding) -> serde_json::Value {
+    let aliases: Vec<_> = finding
+        .trace
+        .aliases
+        .iter()
+        .map(|alias| json!({ "span": json_range(&alias.span), "callee": alias.callee }))
+        .collect();
+    json!({
+        "kind": finding.kind.name(),
+        "level": finding.level,
+        "function": finding.function,
+        "span": json_range(&finding.span),
+        "unwind": finding.unwind,
+        "drop_site": finding.drop_site.as_ref().map(json_range),
+        "trace": { "path": finding.trace.path, "aliases": aliases },
+    })
+}
+
+fn sarif_region(range: &SpanRange) -> serde_json::Value {
+    json!({
+        "artifactLocation": { "uri": range.file },
+        "region": {
+            "startLine": range.start_line,
+            "startColumn": range.start_column,
+            "endLine": range.end_line,
+            "endColumn": range.end_column,
+        },
+    })
+}
+
+//the trace of a finding as a SARIF code flow: the aliasing sites, the drop, and the bug itself.
+fn sarif_code_flow(finding: &Finding) -> serde_json::Value {
+    let mut locations: Vec<_> = finding
+        .trace
+        .aliases
+        .iter()
+        .map(|alias| {
+            let text = match alias.callee {
+                Some(ref callee) => format!("value aliased by the call to `{callee}`"),
+                None => "value aliased here".to_string(),
+            };
+            json!({
+                "location": {
+                    "physicalLocation": sarif_region(&alias.span),
+                    "message": { "text": text },
+                },
+            })
+        })
+        .collect();
+    if let Some(ref drop_site) = finding.drop_site {
+        locations.push(json!({
+            "location": {
+                "physicalLocation": sarif_region(drop_site),
+                "message": { "text": finding.kind.drop_site_message() },
+            },
+        }));
+    }
+    locations.push(json!({
+        "location": {
+            "physicalLocation": sarif_region(&finding.span),
+            "message": { "text": finding.kind.lint().desc },
+        },
+    }));
+    json!({
+        "message": { "text": format!("path to the bug: {}", finding.trace.path.join(" -> ")) },
+        "threadFlows": [{ "locations": locations }],
+    })
+}
+
+fn sarif_log(findings: &[Finding]) -> serde_json::Value {
+    let rules: Vec<_> = BugKind::ALL
+        .iter()
+        .map(|kind| {
+            json!({
+                "id": kind.rule_id(),
+                "name": kind.name(),
+                "shortDescription": { "text": kind.lint().desc },
+            })
+        })
+        .collect();
+    let results: Vec<_> = findings
+        .iter()
+        .map(|finding| {
+            let mut text = finding.kind.lint().desc.to_string();
+            if finding.unwind {
+                text.push_str(" when the function unwinds");
+            }
+            let related: Vec<_> = finding
+                .drop_site
+                .iter()
+                .map(|drop_site| {
+                    json!({
+                        "id": 0,
+                        "physicalLocation": sarif_region(drop_site),
+                        "message": { "text": finding.kind.drop_site_message() },
+                    })
+                })
+                .collect();
+            json!({
+                "ruleId": finding.kind.rule_id(),
+                "ruleIndex": BugKind::ALL.iter().position(|kind| *kind == finding.kind),
+                "level": finding.level,
+                "message": { "text": text },
+                "locations": [{
+                    "physicalLocation": sarif_region(&finding.span),
+                    "logicalLocations": [{
+                        "fullyQualifiedName": finding.function,
+                        "kind": "function",
+                    }],
+                }],
+                "relatedLocations": related,
+                "codeFlows": [sarif_code_flow(finding)],
+                "properties": { "unwind": finding.unwind },
+            })
+        })
+        .collect();
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "SafeDrop",
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
@@ -1,7 +1,9 @@
-use rustc_middle::ty::TyCtxt;
-use rustc_middle::mir::{TerminatorKind, Operand};
+use rustc_middle::ty::{InstanceDef, ParamEnv, TyCtxt};
+use rustc_middle::mir::{Body, Terminator, TerminatorKind, Operand};
+use rustc_session::config::SafeDropMode;
 use rustc_middle::mir::Operand::{Copy, Move, Constant};
-use rustc_data_structures::fx::{FxHashSet, FxHashMap};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_span::def_id::DefId;
 
 use crate::{rap_error, RapLogLevel, record_msg, RAP_LOGGER};
 use log::Log;
@@ -9,41 +11,64 @@
 use super::graph::*;
 use super::alias::*;
 
-pub const DROP:usize = 1634;
-pub const DROP_IN_PLACE:usize = 2160;
-pub const CALL_MUT:usize = 3022;
-pub const NEXT:usize = 7587;
-
 pub const VISIT_LIMIT:usize = 10000;
 
-//struct to cache the results for analyzed functions.
+//struct to hold the summaries of the call graph component being summarized;
+//other callees are summarized by `summary::fn_summary`.
+//shims have no DefId of their own and are summarized from their MIR once per analysis.
 #[derive(Clone)]
-pub struct FuncMap {
-    pub map: FxHashMap<usize, FnRetAlias>,
-    pub set: FxHashSet<usize>,
+pub struct FuncMap<'tcx> {
+    pub map: FxHashMap<DefId, FnRetAlias>,
+    pub shims: FxHashMap<InstanceDef<'tcx>, FnRetAlias>,
+}
+
+impl<'tcx> FuncMap<'tcx> {
+    pub fn new() -> FuncMap<'tcx> {
+        FuncMap { map: FxHashMap::default(), shims: FxHashMap::default() }
+    }
+}
+
+// build the graph of `body` and explore it with the engine selected by `-Z safedrop-mode`.
+pub fn analyze_body<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>, def_id: DefId, func_map: &mut FuncMap<'tcx>) -> SafeDropGraph<'tcx> {
+    analyze_body_in(tcx, body, def_id, tcx.param_env(def_id), func_map)
 }
 
-impl FuncMap {
-    pub fn new() -> FuncMap {
-        FuncMap { map: FxHashMap::default(), set: FxHashSet::default() }
+// like `analyze_body`, with the types of the body checked in `param_env`.
+pub fn analyze_body_in<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>, def_id: DefId, param_env: ParamEnv<'tcx>, func_map: &mut FuncMap<'tcx>) -> SafeDropGraph<'tcx> {
+    let mode = tcx.sess.opts.unstable_opts.safedrop_mode;
+    if mode != SafeDropMode::Dataflow {
+        let mut safedrop_graph = SafeDropGraph::new(body, tcx, def_id, param_env);
+        safedrop_graph.solve_scc();
+        safedrop_graph.check(0, tcx, func_map);
+        // in the auto mode, bodies with too many paths are analyzed again by the dataflow mode.
+        if mode == SafeDropMode::Path || safedrop_graph.visit_times <= VISIT_LIMIT {
+            return safedrop_graph;
+        }
     }
+    let mut safedrop_graph = SafeDropGraph::new(body, tcx, def_id, param_env);
+    safedrop_graph.check_dataflow(body, tcx, func_map);
+    safedrop_graph
 }
 
 impl<'tcx> SafeDropGraph<'tcx> {
     // analyze the drop statement and update the liveness for nodes.
     pub fn drop_check(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>) {
+        self.drop_check_with_birth(bb_index, self.birth_of(bb_index), tcx);
+    }
+
+    // values born before `birth` are killed by the drops of the block.
+    pub fn drop_check_with_birth(&mut self, bb_index: usize, birth: usize, tcx: TyCtxt<'tcx>) {
         let cur_block = self.blocks[bb_index].clone();
         for drop in cur_block.drops{
             match drop.kind{
                 TerminatorKind::Drop{ref place, target: _, unwind: _, replace: _} => {
-                    let birth = self.scc_indices[bb_index];
                     let drop_local = self.projection(tcx, false, place.clone());
                     let info = drop.source_info.clone();
+                    self.uninit_drop_check(drop_local, info.span);
                     self.dead_node(drop_local, birth, &info, false);
                 },
                 TerminatorKind::Call { func: _,  ref args, .. } => {
                     if args.len() > 0 {
-                        let birth = self.scc_indices[bb_index];
                     	let place = match args[0] {
                         	Operand::Copy(place) => place,
                         	Operand::Move(place) => place,
@@ -51,6 +76,7 @@ pub fn drop_check(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>) {
                     	};
                     	let drop_local = self.projection(tcx, false, place.clone());
                     	let info = drop.source_info.clone();
+                    	self.uninit_drop_check(drop_local, info.span);
                     	self.dead_node(drop_local, birth, &info, false);
 		    }
                 },
@@ -59,7 +85,7 @@ pub fn drop_check(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>) {
         }
     }
 
-    pub fn split_check(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>, func_map: &mut FuncMap) {
+    pub fn split_check(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>, func_map: &mut FuncMap<'tcx>) {
         /* duplicate the status before visiting a path; */
         let backup_values = self.values.clone(); // duplicate the status when visiting different paths;
         let backup_constant = self.constant.clone();
@@ -68,44 +94,146 @@ pub fn split_check(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>, func_map: &mut
         self.values = backup_values;
         self.constant = backup_constant;
     }
-    pub fn split_check_with_cond(&mut self, bb_index: usize, path_discr_id: usize, path_discr_val:usize, tcx: TyCtxt<'tcx>, func_map: &mut FuncMap) {
+    pub fn split_check_with_cond(&mut self, bb_index: usize, path_discr_id: usize, path_discr_val:usize, tcx: TyCtxt<'tcx>, func_map: &mut FuncMap<'tcx>) {
         /* duplicate the status before visiting a path; */
         let backup_values = self.values.clone(); // duplicate the status when visiting different paths;
         let backup_constant = self.constant.clone();
-        /* add control-sensitive indicator to the path status */ 
+        /* add control-sensitive indicator to the path status, the value and its aliases have the same discriminant */ 
         self.constant.insert(path_discr_id, path_discr_val);
+        for alias in self.values[path_discr_id].alias.clone() {
+            self.constant.insert(alias, path_discr_val);
+        }
         self.check(bb_index, tcx, func_map);
         /* restore after visit */ 
         self.values = backup_values;
         self.constant = backup_constant;
     }
 
+    /*
+     * Run the blocks of a merged scc again and again, so that values dropped in one iteration
+     * are seen dead by the next one. Each round gives its assignments a later birth than the
+     * previous one. We stop after `-Z safedrop-loop-bound` rounds, or earlier once a round
+     * changes neither the aliases nor the liveness of any value.
+     * Blocks that can only be reached through switch targets ruled out by a known discriminant
+     * are skipped. Returns the exits of the scc that can be reached in some round.
+     */
+    pub fn unroll_scc(&mut self, scc: usize, tcx: TyCtxt<'tcx>, func_map: &mut FuncMap<'tcx>) -> FxHashSet<usize> {
+        let bound = tcx.sess.opts.unstable_opts.safedrop_loop_bound.max(1);
+        let mut scc_blocks = vec![scc];
+        scc_blocks.extend(self.blocks[scc].scc_sub_blocks.iter());
+        let mut exits = FxHashSet::default();
+        let mut last_state = None;
+        for round in 0..bound {
+            self.loop_round = round;
+            let mut skipped = FxHashSet::default();
+            let mut ruled_out = FxHashSet::default(); // edges (from, to) not taken.
+            for i in scc_blocks.iter() {
+                let i = *i;
+                if i != scc && self.blocks[i].preds.iter().all(|pred| skipped.contains(pred) || ruled_out.contains(&(*pred, i))) {
+                    skipped.insert(i);
+                    continue;
+                }
+                self.alias_bb(i, tcx);
+                self.uninit_bb(i, tcx);
+                self.alias_bbcall(i, tcx, func_map);
+                self.drop_check(i, tcx);
+                if let Some(switch) = self.blocks[i].switch_stmts.first().cloned()
+                    && let Some(taken) = self.switch_target(&switch, tcx)
+                    && let TerminatorKind::SwitchInt { ref targets, .. } = switch.kind
+                {
+                    for target in targets.all_targets() {
+                        if target.as_usize() != taken {
+                            ruled_out.insert((i, target.as_usize()));
+                        }
+                    }
+                }
+            }
+            for exit in self.blocks[scc].next.iter() {
+                if self.blocks[*exit].preds.iter().any(|pred| self.scc_indices[*pred] == scc && !skipped.contains(pred) && !ruled_out.contains(&(*pred, *exit))) {
+                    exits.insert(*exit);
+                }
+            }
+            let state: Vec<(Vec<usize>, bool)> = self.values.iter().map(|value| (value.alias.clone(), value.is_alive())).collect();
+            if last_state.as_ref() == Some(&state) {
+                break;
+            }
+            last_state = Some(state);
+        }
+        // after the loop, the values born in any of its rounds count as born in the scc.
+        self.loop_round = 0;
+        let first_unrolled = self.blocks.len() as isize;
+        for value in self.values.iter_mut() {
+            if value.birth >= first_unrolled {
+                value.birth = scc as isize;
+            }
+        }
+        return exits;
+    }
+
+    // the only target of a SwitchInt that can be taken, if its discriminant is known on the current path.
+    pub fn switch_target(&mut self, switch: &Terminator<'tcx>, tcx: TyCtxt<'tcx>) -> Option<usize> {
+        let TerminatorKind::SwitchInt { ref discr, ref targets } = switch.kind else {
+            return None;
+        };
+        let sw_val = match discr {
+            Copy(p) | Move(p) => {
+                let place = self.projection(tcx, false, p.clone());
+                *self.constant.get(&self.values[place].alias[0])?
+            }
+            Constant(c) => {
+                let param_env = self.param_env;
+                c.const_.try_eval_target_usize(tcx, param_env).map_or(0, |val| val as usize)
+            }
+        };
+        /* Find the target based on the value;
+         * Since sw_val is a const, only one target is reachable.
+         * Filed 0 is the value; field 1 is the real target.
+         * If no target is found, it is the default target.
+         */
+        for iter in targets.iter() {
+            if iter.0 as usize == sw_val {
+                return Some(iter.1.as_usize());
+            }
+        }
+        return Some(targets.otherwise().as_usize());
+    }
+
     // the core function of the safedrop.
-    pub fn check(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>, func_map: &mut FuncMap) {
+    pub fn check(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>, func_map: &mut FuncMap<'tcx>) {
         self.visit_times += 1;
         if self.visit_times > VISIT_LIMIT {
             return;
         }
+        self.path.push(self.scc_indices[bb_index]);
+        self.check_block(bb_index, tcx, func_map);
+        self.path.pop();
+    }
+
+    // check a block and the paths after it.
+    fn check_block(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>, func_map: &mut FuncMap<'tcx>) {
         let cur_block = self.blocks[self.scc_indices[bb_index]].clone();
-        self.alias_bb(self.scc_indices[bb_index], tcx);
-        self.alias_bbcall(self.scc_indices[bb_index], tcx, func_map);
-        self.drop_check(self.scc_indices[bb_index], tcx);
+        let mut next_blocks = cur_block.next.clone();
 
         /* Handle cases if the current block is a merged scc block with sub block */
         if cur_block.scc_sub_blocks.len() > 0{
-            for i in cur_block.scc_sub_blocks.clone(){
-                self.alias_bb(i, tcx);
-                self.alias_bbcall(i, tcx, func_map);
-                self.drop_check(i, tcx);
+            let exits = self.unroll_scc(self.scc_indices[bb_index], tcx, func_map);
+            if !exits.is_empty() {
+                next_blocks = exits;
             }
+        } else {
+            self.alias_bb(self.scc_indices[bb_index], tcx);
+            self.uninit_bb(self.scc_indices[bb_index], tcx);
+            self.alias_bbcall(self.scc_indices[bb_index], tcx, func_map);
+            self.drop_check(self.scc_indices[bb_index], tcx);
         }
 
         /* Reach a leaf node, check bugs */
-        match cur_block.next.len() {
+        match next_blocks.len() {
             0 => { // check the bugs.
-                if Self::should_check(self.def_id){
+                if Self::should_check(self.tcx, self.def_id){
                     self.dp_check(&cur_block);
                 }
+                self.leak_check(&cur_block);
                 // merge the result.
                 let results_nodes = self.values.clone();
                 self.merge_results(results_nodes, cur_block.is_cleanup);
@@ -116,7 +244,7 @@ pub fn check(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>, func_map: &mut FuncM
                 * Equivalent to self.check(cur_block.next[0]..); 
                 * We cannot use [0] for FxHashSet.
                 */
-                for next in cur_block.next {
+                for next in next_blocks {
                     self.check(next, tcx, func_map);
                 }
                 return;
@@ -126,59 +254,23 @@ pub fn check(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>, func_map: &mut FuncM
         }
 
         /* Begin: handle the SwitchInt statement. */
-        let mut single_target = false;
-        let mut sw_val = 0;
-        let mut sw_target = 0; // Single target
+        let mut sw_target = None; // Single target
         let mut path_discr_id = 0; // To avoid analyzing paths that cannot be reached with one enum type.
         let mut sw_targets = None; // Multiple targets of SwitchInt
         if !cur_block.switch_stmts.is_empty() && cur_block.scc_sub_blocks.is_empty() {
-            if let TerminatorKind::SwitchInt { ref discr, ref targets } = cur_block.switch_stmts[0].clone().kind {
-                match discr {
-                    Copy(p) | Move(p) => {
-                        let place = self.projection(tcx, false, p.clone());
-                        if let Some(constant) = self.constant.get(&self.values[place].alias[0]) {
-                            single_target = true;
-                            sw_val = *constant;
-                        }
-                        if self.values[place].alias[0] != place{
-                            path_discr_id = self.values[place].alias[0];
-                            sw_targets = Some(targets.clone());
-                        }
-                    } 
-                    Constant(c) => {
-                        single_target = true;
-                        let param_env = tcx.param_env(self.def_id);
-                        if let Some(val) = c.const_.try_eval_target_usize(tcx, param_env) {
-                            sw_val = val as usize;
-                        }
-
-                    }
-                }
-                if single_target {
-                    /* Find the target based on the value; 
-                     * Since sw_val is a const, only one target is reachable.
-                     * Filed 0 is the value; field 1 is the real target.
-                     */
-                    for iter in targets.iter() {
-                        if iter.0 as usize == sw_val as usize {
-                            sw_target = iter.1.as_usize();
-                            break;
-                        }
-                    }
-                    /* No target found, choose the default target.
-                     * The default targets is not included within the iterator.
-                     * We can only obtain the default target based on the last item of all_targets().
-                     */
-                    if sw_target == 0 {
-                        let all_target = targets.all_targets();
-                        sw_target = all_target[all_target.len()-1].as_usize();
-                    }
+            let switch = cur_block.switch_stmts[0].clone();
+            sw_target = self.switch_target(&switch, tcx);
+            if let TerminatorKind::SwitchInt { discr: Copy(ref p) | Move(ref p), ref targets } = switch.kind {
+                let place = self.projection(tcx, false, p.clone());
+                if self.values[place].alias[0] != place {
+                    path_discr_id = self.values[place].alias[0];
+                    sw_targets = Some(targets.clone());
                 }
             }
         }
         /* End: finish handling SwitchInt */
         // fixed path since a constant switchInt value
-        if single_target {
+        if let Some(sw_target) = sw_target {
             self.check(sw_target, tcx, func_map);
         } else {
             // Other cases in switchInt terminators
@@ -196,7 +288,7 @@ pub fn check(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>, func_map: &mut FuncM
                 let path_discr_val = usize::MAX; // to indicate the default path;
                 self.split_check_with_cond(next_index, path_discr_id, path_discr_val, tcx, func_map);
             } else {
-                for i in cur_block.next {
+                for i in next_blocks {
                     if self.visit_times > VISIT_LIMIT {
                         continue;
                     }
@@ -0,0 +1,115 @@
+//! The effects of library and FFI functions, declared in the TOML file given by
+//! `-Z safedrop-spec`. Calls to the declared functions are modelled by their declaration
+//! instead of a summary or a guess, e.g.,
+//!
+//! ```toml
+//! [[function]]
+//! name = "free"
+//! frees = [0]
+//!
+//! [[function]]
+//! name = "mylib::Buffer::data"
+//! aliases_return = [0]
+//! returns = "borrowed"
+//! ```
+//!
+//! `name` is the path of the function, or the name of a foreign function. Arguments are
+//! numbered from 0: `frees` lists the arguments whose targets the function frees, and
+//! `aliases_return` the arguments that the returned value points into. The returned value
+//! is `owned` (the default) if the caller has to free it, and `borrowed` otherwise.
+
+use std::error::Error;
+use std::path::Path;
+
+use rustc_data_structures::fx::FxIndexMap;
+pub use rustc_middle::mir::safedrop::{FnSpec, Ownership};
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::def_id::DefId;
+use serde::Deserialize;
+
+use crate::errors;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SpecOwnership {
+    #[default]
+    Owned,
+    Borrowed,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpecFn {
+    name: String,
+    #[serde(default)]
+    frees: Vec<usize>,
+    #[serde(default)]
+    aliases_return: Vec<usize>,
+    #[serde(default)]
+    returns: SpecOwnership,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpecFile {
+    #[serde(default, rename = "function")]
+    functions: Vec<SpecFn>,
+}
+
+/// Parses the file given by `-Z safedrop-spec`, if any.
+pub fn safedrop_specs(tcx: TyCtxt<'_>, (): ()) -> FxIndexMap<String, FnSpec> {
+    let Some(ref path) = tcx.sess.opts.unstable_opts.safedrop_spec else {
+        return FxIndexMap::default();
+    };
+    match read_specs(tcx, path) {
+        Ok(specs) => specs,
+        Err(err) => {
+            tcx.sess.emit_err(errors::SafeDropSpecError {
+                path: path.display().to_string(),
+                error: err.to_string(),
+            });
+            FxIndexMap::default()
+        }
+    }
+}
+
+//the file is loaded into the source map, so that it is listed in the dep-info like an
+//included file.
+fn read_specs(tcx: TyCtxt<'_>, path: &Path) -> Result<FxIndexMap<String, FnSpec>, Box<dyn Error>> {
+    let source = tcx.sess.source_map().load_file(path)?;
+    let file: SpecFile = toml::from_str(source.src.as_deref().map_or("", |src| src.as_str()))?;
+    Ok(file
+        .functions
+        .into_iter()
+        .map(|spec| {
+            let returns = match spec.returns {
+                SpecOwnership::Owned => Ownership::Owned,
+                SpecOwnership::Borrowed => Ownership::Borrowed,
+            };
+            let spec = FnSpec {
+                name: spec.name,
+                frees: spec.frees,
+                aliases_return: spec.aliases_return,
+                returns,
+            };
+            (spec.name.clone(), spec)
+        })
+        .collect())
+}
+
+/// The declared effects of the callee, looked up by its path, or by its name if it is foreign.
+pub fn fn_spec(tcx: TyCtxt<'_>, def_id: DefId) -> Option<&FnSpec> {
+    let specs = tcx.safedrop_specs(());
+    if specs.is_empty() {
+        return None;
+    }
+    let path = with_no_trimmed_paths!(tcx.def_path_str(def_id));
+    if let Some(spec) = specs.get(&path) {
+        return Some(spec);
+    }
+    if tcx.is_foreign_item(def_id) {
+        return specs.get(tcx.item_name(def_id).as_str());
+    }
+    None
+}
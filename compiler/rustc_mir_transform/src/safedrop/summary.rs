@@ -0,0 +1,273 @@
+use rustc_data_structures::fx::{FxHashSet, FxIndexMap, FxIndexSet};
+use rustc_data_structures::graph::scc::Sccs;
+use rustc_data_structures::graph::vec_graph::VecGraph;
+use rustc_hir::def::DefKind;
+use rustc_middle::mir::safedrop::{FnRetAlias, RetAlias, SafeDropCallGraph};
+use rustc_middle::mir::{Body, TerminatorKind, RETURN_PLACE};
+use rustc_middle::ty::{self, GenericArgs, GenericArgsRef, Instance, InstanceDef, TyCtxt};
+use rustc_span::def_id::{DefId, LocalDefId};
+
+use super::graph::{call_targets, fn_ptr_targets};
+use super::safedrop::{analyze_body, FuncMap};
+use super::types::is_not_drop;
+use crate::{rap_info, record_msg, RapLogLevel, RAP_LOGGER};
+use log::Log;
+
+//bound on the rounds of the fixed-point iteration over a recursive component of the call graph;
+//the functions of a component that has not converged by then get conservative summaries.
+pub const SUMMARY_ROUND_LIMIT: usize = 32;
+
+//bound on the impls whose summaries are joined for a call through a trait object.
+pub const VIRTUAL_CALLEE_LIMIT: usize = 16;
+
+//the MIR a summary is computed from, if `def_id` is a local function that has one.
+//local functions are summarized from the MIR of the phase their callers are analysed in.
+pub fn summary_body<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId) -> Option<&'tcx Body<'tcx>> {
+    let local = def_id.as_local()?;
+    match tcx.def_kind(def_id) {
+        DefKind::Fn | DefKind::AssocFn | DefKind::Closure => {}
+        _ => return None,
+    }
+    if !tcx.is_mir_available(def_id) {
+        return None;
+    }
+    Some(tcx.safedrop_mir(local))
+}
+
+//shims are summarized from `instance_mir`; intrinsics have no MIR and virtual calls are resolved to their impls.
+fn is_shim(def: InstanceDef<'_>) -> bool {
+    !matches!(def, InstanceDef::Item(_) | InstanceDef::Intrinsic(_) | InstanceDef::Virtual(..))
+}
+
+//the impls of the trait method `method` that a call through a trait object may run.
+fn virtual_callees<'tcx>(tcx: TyCtxt<'tcx>, method: DefId) -> Vec<Instance<'tcx>> {
+    let Some(trait_id) = tcx.trait_of_item(method) else {
+        return Vec::new();
+    };
+    let mut callees = Vec::new();
+    for impl_id in tcx.all_impls(trait_id) {
+        let item = match tcx.impl_item_implementor_ids(impl_id).get(&method) {
+            Some(&item) => item,
+            None if tcx.defaultness(method).has_value() => method,
+            None => continue,
+        };
+        let callee = Instance::new(item, GenericArgs::identity_for_item(tcx, item));
+        if !callees.contains(&callee) {
+            callees.push(callee);
+        }
+    }
+    //too many candidates say nothing about the call, and summarizing all of them is costly.
+    if callees.len() > VIRTUAL_CALLEE_LIMIT {
+        return Vec::new();
+    }
+    callees
+}
+
+//the instances a call of `def_id` with `args` in a body with `param_env` may run.
+//a call that cannot be resolved yet, e.g., of a method of a type parameter, runs the item itself.
+pub fn resolve_callees<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    def_id: DefId,
+    args: GenericArgsRef<'tcx>,
+) -> Vec<Instance<'tcx>> {
+    let resolved = match tcx.try_normalize_erasing_regions(param_env, args) {
+        Ok(args) => Instance::resolve(tcx, param_env, def_id, args),
+        Err(_) => Ok(None),
+    };
+    match resolved {
+        Ok(Some(instance)) => match instance.def {
+            InstanceDef::Virtual(method, _) => virtual_callees(tcx, method),
+            _ => vec![instance],
+        },
+        _ => vec![Instance::new(def_id, args)],
+    }
+}
+
+//the summary of a resolved callee: functions by their DefId, and shims, e.g., drop glue and closure shims,
+//from the MIR built for the instance.
+pub fn callee_summary<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    instance: Instance<'tcx>,
+    func_map: &mut FuncMap<'tcx>,
+) -> Option<FnRetAlias> {
+    match instance.def {
+        InstanceDef::Item(def_id) => {
+            //functions of the call graph component being summarized use the summaries of the current round.
+            if let Some(summary) = func_map.map.get(&def_id) {
+                return Some(summary.clone());
+            }
+            fn_summary(tcx, def_id).cloned()
+        }
+        def if is_shim(def) => {
+            if let Some(summary) = func_map.shims.get(&def) {
+                return Some(summary.clone());
+            }
+            let body = tcx.instance_mir(def);
+            //a shim reached again while it is summarized, e.g., the drop glue of a recursive type, has no effect yet.
+            func_map.shims.insert(def, FnRetAlias::new(body.arg_count));
+            let summary = analyze_body(tcx, body, instance.def_id(), func_map).ret_alias;
+            func_map.shims.insert(def, summary.clone());
+            Some(summary)
+        }
+        _ => None,
+    }
+}
+
+//the local functions called in `body`, with trait methods and function pointers resolved and shims looked through.
+//upstream functions are not walked: their summaries are the ones stored in the metadata of their crate.
+pub fn safedrop_callees(tcx: TyCtxt<'_>, def_id: LocalDefId) -> &[LocalDefId] {
+    let Some(body) = summary_body(tcx, def_id.to_def_id()) else {
+        return &[];
+    };
+    let mut callees = FxIndexSet::default();
+    let mut shims = FxHashSet::default();
+    let mut bodies = vec![body];
+    while let Some(body) = bodies.pop() {
+        let param_env = tcx.param_env(body.source.def_id());
+        let fn_ptrs = fn_ptr_targets(body);
+        for block in body.basic_blocks.iter() {
+            let TerminatorKind::Call { ref func, .. } = block.terminator().kind else {
+                continue;
+            };
+            for (def_id, args) in call_targets(func, &fn_ptrs) {
+                for instance in resolve_callees(tcx, param_env, def_id, args) {
+                    match instance.def {
+                        InstanceDef::Item(def_id) => {
+                            if summary_body(tcx, def_id).is_some() {
+                                callees.insert(def_id.expect_local());
+                            }
+                        }
+                        def if is_shim(def) => {
+                            if shims.insert(def) {
+                                bodies.push(tcx.instance_mir(def));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+    tcx.arena.alloc_from_iter(callees)
+}
+
+pub fn safedrop_call_graph(tcx: TyCtxt<'_>, (): ()) -> SafeDropCallGraph {
+    let mut nodes: FxIndexSet<LocalDefId> = tcx
+        .hir()
+        .body_owners()
+        .filter(|&def_id| summary_body(tcx, def_id.to_def_id()).is_some())
+        .collect();
+    let mut edges = Vec::new();
+    let mut i = 0;
+    while i < nodes.len() {
+        for &callee in tcx.safedrop_callees(nodes[i]) {
+            let (j, _) = nodes.insert_full(callee);
+            edges.push((i, j));
+        }
+        i += 1;
+    }
+
+    let graph = VecGraph::new(nodes.len(), edges.clone());
+    let sccs: Sccs<usize, usize> = Sccs::new(&graph);
+    let mut call_graph = SafeDropCallGraph {
+        scc_of: FxIndexMap::default(),
+        members: vec![Vec::new(); sccs.num_sccs()],
+        recursive: vec![false; sccs.num_sccs()],
+    };
+    for (i, &def_id) in nodes.iter().enumerate() {
+        let scc = sccs.scc(i);
+        call_graph.members[scc].push(def_id.to_def_id());
+        call_graph.scc_of.insert(def_id.to_def_id(), scc);
+    }
+    for (i, j) in edges {
+        let scc = sccs.scc(i);
+        if scc == sccs.scc(j) {
+            call_graph.recursive[scc] = true;
+        }
+    }
+    call_graph
+}
+
+//run SafeDrop on `def_id`, with the summaries of its own component taken from `func_map`.
+fn summarize<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId, func_map: &mut FuncMap<'tcx>) -> FnRetAlias {
+    let Some(body) = summary_body(tcx, def_id) else {
+        return FnRetAlias::new(0);
+    };
+    analyze_body(tcx, body, def_id, func_map).ret_alias
+}
+
+//the summary assumed for a function whose component does not converge: the return value may alias
+//every pointer argument, and every pointer argument may be dropped.
+fn conservative_summary<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId) -> FnRetAlias {
+    let Some(body) = summary_body(tcx, def_id) else {
+        return FnRetAlias::new(0);
+    };
+    let param_env = tcx.param_env(def_id);
+    let mut summary = FnRetAlias::new(body.arg_count);
+    let ret_ty = body.local_decls[RETURN_PLACE].ty;
+    let ret_may_drop = !is_not_drop(tcx, ret_ty);
+    for arg in body.args_iter() {
+        let ty = body.local_decls[arg].ty;
+        if !ty.is_any_ptr() {
+            continue;
+        }
+        summary.dead.insert(arg.as_usize());
+        if ret_may_drop {
+            summary.alias_vec.push(RetAlias::new(
+                0,
+                0,
+                true,
+                ret_ty.needs_drop(tcx, param_env),
+                arg.as_usize(),
+                true,
+                ty.needs_drop(tcx, param_env),
+            ));
+        }
+    }
+    summary
+}
+
+pub fn safedrop_scc_summaries(tcx: TyCtxt<'_>, scc: usize) -> FxIndexMap<DefId, FnRetAlias> {
+    let call_graph = tcx.safedrop_call_graph(());
+    let members = &call_graph.members[scc];
+
+    //start from empty summaries and grow them until no function of the component changes.
+    let mut func_map = FuncMap::new();
+    for &def_id in members {
+        let arg_size = summary_body(tcx, def_id).map_or(0, |body| body.arg_count);
+        func_map.map.insert(def_id, FnRetAlias::new(arg_size));
+    }
+    let mut converged = false;
+    for _ in 0..SUMMARY_ROUND_LIMIT {
+        let mut changed = false;
+        for &def_id in members {
+            let summary = summarize(tcx, def_id, &mut func_map);
+            changed |= func_map.map.get_mut(&def_id).unwrap().join(&summary);
+        }
+        if !changed || !call_graph.recursive[scc] {
+            converged = true;
+            break;
+        }
+    }
+    //the field paths in the summaries of recursive data structures may keep growing.
+    if !converged {
+        rap_info!("Summaries not converged after {} rounds: {:?}", SUMMARY_ROUND_LIMIT, members);
+        return members.iter().map(|&def_id| (def_id, conservative_summary(tcx, def_id))).collect();
+    }
+    members.iter().map(|def_id| (*def_id, func_map.map.remove(def_id).unwrap())).collect()
+}
+
+//the summary of a call to `def_id`: local functions are summarized together with their component of the
+//call graph, upstream functions only have the summaries stored in the metadata of their crate.
+pub fn fn_summary<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId) -> Option<&'tcx FnRetAlias> {
+    if !def_id.is_local() {
+        return tcx.safedrop_ret_alias(def_id).as_ref();
+    }
+    let scc = tcx.safedrop_call_graph(()).scc(def_id)?;
+    Some(&tcx.safedrop_scc_summaries(scc)[&def_id])
+}
+
+pub fn safedrop_ret_alias(tcx: TyCtxt<'_>, def_id: LocalDefId) -> Option<FnRetAlias> {
+    fn_summary(tcx, def_id.to_def_id()).cloned()
+}
@@ -1,27 +1,34 @@
 use rustc_middle::ty;
 use rustc_middle::ty::{Ty, TyCtxt};
-use super::corner_handle::is_corner_adt;
+use rustc_span::symbol::sym;
 
-#[derive(PartialEq,Debug,Copy,Clone)]
+#[derive(PartialEq,Eq,Debug,Copy,Clone)]
 pub enum TyKind {
     Adt,
     RawPtr,
     Tuple,
-    CornerCase,
+    Box,
+    // Rc and Arc: every value owns one count of the shared allocation.
+    RcPtr,
+    // Weak pointers of Rc and Arc, dropping them frees nothing.
+    WeakPtr,
+    // the Ref and RefMut guards of RefCell, dropping them only releases a borrow.
+    CellGuard,
     Ref,
 }
 
-pub fn kind<'tcx>(current_ty: Ty<'tcx>) -> TyKind {
+pub fn kind<'tcx>(tcx: TyCtxt<'tcx>, current_ty: Ty<'tcx>) -> TyKind {
     match current_ty.kind() {
         ty::RawPtr(..) => TyKind::RawPtr,
         ty::Ref(..) => TyKind::Ref,
         ty::Tuple(..) => TyKind::Tuple,
+        ty::Adt(ref adt_def, _) if adt_def.is_box() => TyKind::Box,
         ty::Adt(ref adt_def, _) => {
-            if is_corner_adt(format!("{:?}", adt_def)) {
-                return TyKind::CornerCase;
-            }
-            else{
-                return TyKind::Adt;
+            match tcx.get_diagnostic_name(adt_def.did()) {
+                Some(sym::Rc | sym::Arc) => TyKind::RcPtr,
+                Some(sym::RcWeak | sym::ArcWeak) => TyKind::WeakPtr,
+                Some(sym::RefCellRef | sym::RefCellRefMut) => TyKind::CellGuard,
+                _ => TyKind::Adt,
             }
         },
         _ => TyKind::Adt,
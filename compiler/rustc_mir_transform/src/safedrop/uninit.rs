@@ -0,0 +1,284 @@
+//! The initialization state of memory, for the uninit checker (`-Z safedrop=uninit`).
+//! Memory that is created without being initialized, e.g., by `MaybeUninit::uninit`,
+//! `mem::uninitialized` or `alloc`, is `Uninit` until it is written, and reading or
+//! dropping it before is reported. The state follows the aliases of a value like its
+//! liveness does; a call that is given a mutable pointer to the memory may initialize it.
+
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::{LangItem, Mutability};
+use rustc_middle::mir::tcx::PlaceTy;
+use rustc_middle::mir::{Operand, Place};
+use rustc_middle::ty::{self, GenericArgsRef, Ty, TyCtxt};
+use rustc_span::def_id::DefId;
+use rustc_span::symbol::sym;
+use rustc_span::Span;
+
+use super::bug_records::BugSite;
+use super::corner_handle::{is_drop_fn, SpecialItem};
+use super::graph::{InitState, SafeDropGraph};
+use super::utils::is_zero_count;
+
+/// How a call changes, or depends on, the initialization state of memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitEffect {
+    /// The returned value, or what the returned pointer points to, is not initialized.
+    ReturnsUninit,
+    /// The returned value holds memory that is not initialized but not in use either,
+    /// e.g., the spare capacity of a vector.
+    ReturnsPartiallyInit,
+    /// The memory held by the first argument comes into use as it is, e.g., `Vec::set_len`.
+    Exposes,
+    /// The value the first argument holds or points to is read.
+    Reads,
+    /// The value the first argument points to is dropped.
+    Drops,
+}
+
+#[derive(Debug)]
+pub struct InitFn {
+    pub path: &'static str,
+    pub item: SpecialItem,
+    pub effect: InitEffect,
+}
+
+/// The functions whose effect on the initialization of memory SafeDrop knows.
+/// To support a new function, add an entry here.
+pub static INIT_FNS: &[InitFn] = &[
+    InitFn {
+        path: "core::mem::uninitialized",
+        item: SpecialItem::Diagnostic(sym::mem_uninitialized),
+        effect: InitEffect::ReturnsUninit,
+    },
+    InitFn {
+        path: "core::mem::MaybeUninit::uninit",
+        item: SpecialItem::Diagnostic(sym::maybe_uninit_uninit),
+        effect: InitEffect::ReturnsUninit,
+    },
+    InitFn {
+        path: "alloc::alloc::alloc",
+        item: SpecialItem::Path("alloc::alloc::alloc"),
+        effect: InitEffect::ReturnsUninit,
+    },
+    InitFn {
+        path: "alloc::vec::Vec::with_capacity",
+        item: SpecialItem::Method(sym::Vec, sym::with_capacity),
+        effect: InitEffect::ReturnsPartiallyInit,
+    },
+    InitFn {
+        path: "alloc::vec::Vec::set_len",
+        item: SpecialItem::Method(sym::Vec, sym::set_len),
+        effect: InitEffect::Exposes,
+    },
+    InitFn {
+        path: "core::mem::MaybeUninit::assume_init",
+        item: SpecialItem::LangMethod(LangItem::MaybeUninit, sym::assume_init),
+        effect: InitEffect::Reads,
+    },
+    InitFn {
+        path: "core::mem::MaybeUninit::assume_init_read",
+        item: SpecialItem::LangMethod(LangItem::MaybeUninit, sym::assume_init_read),
+        effect: InitEffect::Reads,
+    },
+    InitFn {
+        path: "core::mem::MaybeUninit::assume_init_ref",
+        item: SpecialItem::LangMethod(LangItem::MaybeUninit, sym::assume_init_ref),
+        effect: InitEffect::Reads,
+    },
+    InitFn {
+        path: "core::mem::MaybeUninit::assume_init_mut",
+        item: SpecialItem::LangMethod(LangItem::MaybeUninit, sym::assume_init_mut),
+        effect: InitEffect::Reads,
+    },
+    InitFn {
+        path: "core::mem::MaybeUninit::assume_init_drop",
+        item: SpecialItem::LangMethod(LangItem::MaybeUninit, sym::assume_init_drop),
+        effect: InitEffect::Drops,
+    },
+    InitFn {
+        path: "core::ptr::read",
+        item: SpecialItem::Diagnostic(sym::ptr_read),
+        effect: InitEffect::Reads,
+    },
+    InitFn {
+        path: "core::ptr::read_unaligned",
+        item: SpecialItem::Diagnostic(sym::ptr_read_unaligned),
+        effect: InitEffect::Reads,
+    },
+];
+
+pub fn init_effect<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    args: GenericArgsRef<'tcx>,
+) -> Option<InitEffect> {
+    INIT_FNS
+        .iter()
+        .find(|init_fn| init_fn.item.matches(tcx, def_id, args))
+        .map(|init_fn| init_fn.effect)
+}
+
+//values of these types may hold memory that is not initialized: MaybeUninit, and arrays or tuples of it.
+fn allows_uninit<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
+    match ty.kind() {
+        ty::Adt(adt_def, _) => tcx.lang_items().get(LangItem::MaybeUninit) == Some(adt_def.did()),
+        ty::Array(elem, _) | ty::Slice(elem) => allows_uninit(tcx, *elem),
+        ty::Tuple(tys) => tys.iter().all(|ty| allows_uninit(tcx, ty)),
+        _ => false,
+    }
+}
+
+impl<'tcx> SafeDropGraph<'tcx> {
+    /// Applies the reads and writes of the statements of a block, after their aliases.
+    pub fn uninit_bb(&mut self, bb_index: usize, tcx: TyCtxt<'tcx>) {
+        let cur_block = self.blocks[bb_index].clone();
+        for access in cur_block.accesses {
+            if access.is_write {
+                let node = self.projection(tcx, false, access.place);
+                self.init_written(node);
+            } else {
+                let node = self.projection(tcx, true, access.place);
+                let ty = self.place_ty(&access.place);
+                self.uninit_read_check(node, ty, access.span);
+            }
+        }
+    }
+
+    /// Applies a call to the initialization state; `merge_vec` holds the destination and then the arguments.
+    pub fn uninit_call(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        targets: &Vec<(DefId, GenericArgsRef<'tcx>)>,
+        merge_vec: &Vec<usize>,
+        args: &[Operand<'tcx>],
+        span: Span,
+    ) {
+        let mut effect = None;
+        if let [(target_id, target_args)] = targets[..] {
+            //the drops of a block are checked with the block.
+            if is_drop_fn(tcx, target_id, target_args) {
+                return;
+            }
+            effect = init_effect(tcx, target_id, target_args).map(|effect| (effect, target_args));
+        }
+        let first = merge_vec.get(1).copied().filter(|node| *node != 0);
+        match effect {
+            Some((InitEffect::ReturnsUninit, _)) => {
+                self.values[merge_vec[0]].set_init(InitState::Uninit, Some(span));
+            }
+            Some((InitEffect::ReturnsPartiallyInit, _)) => {
+                self.values[merge_vec[0]].set_init(InitState::PartiallyInit, Some(span));
+            }
+            Some((InitEffect::Exposes, _)) => {
+                let param_env = self.param_env;
+                if let Some(first) = first
+                    && !is_zero_count(tcx, param_env, args)
+                {
+                    for node in self.init_aliases(first) {
+                        if self.values[node].init == InitState::PartiallyInit {
+                            self.values[node].init = InitState::Uninit;
+                        }
+                    }
+                }
+            }
+            Some((effect @ (InitEffect::Reads | InitEffect::Drops), target_args)) => {
+                if let Some(first) = first
+                    && !target_args.is_empty()
+                    && !allows_uninit(tcx, target_args.type_at(0))
+                    && let Some(uninit) = self.find_uninit(first)
+                {
+                    self.record_uninit(first, uninit, span, effect == InitEffect::Drops);
+                }
+            }
+            None => {
+                // other functions may initialize what their mutable arguments point to, and read what they take by value.
+                for (arg, node) in args.iter().zip(merge_vec.iter().skip(1)) {
+                    let Some(place) = arg.place() else {
+                        continue;
+                    };
+                    let ty = self.place_ty(&place);
+                    match ty.kind() {
+                        ty::RawPtr(ty::TypeAndMut { mutbl: Mutability::Mut, .. })
+                        | ty::Ref(_, _, Mutability::Mut) => {
+                            for alias in self.init_aliases(*node) {
+                                self.values[alias].set_init(InitState::Init, None);
+                            }
+                        }
+                        _ => self.uninit_read_check(*node, ty, span),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reports the drop of a value that is not initialized.
+    pub fn uninit_drop_check(&mut self, node: usize, span: Span) {
+        if !self.values[node].is_alive() {
+            return;
+        }
+        if let Some(uninit) = self.find_uninit(node) {
+            self.record_uninit(node, uninit, span, true);
+        }
+    }
+
+    //a read of a pointer is not a read of what it points to, and MaybeUninit may be read as it is.
+    fn uninit_read_check(&mut self, node: usize, ty: Ty<'tcx>, span: Span) {
+        if ty.is_unsafe_ptr() || ty.is_ref() || allows_uninit(self.tcx, ty) {
+            return;
+        }
+        if let Some(uninit) = self.find_uninit(node) {
+            self.record_uninit(node, uninit, span, false);
+        }
+    }
+
+    fn record_uninit(&mut self, node: usize, uninit: usize, span: Span, is_drop: bool) {
+        let site = BugSite::new(span, self.values[uninit].uninit_site, self.trace(node, uninit));
+        let bugs = if is_drop {
+            &mut self.bug_records.uninit_drop_bugs
+        } else {
+            &mut self.bug_records.uninit_read_bugs
+        };
+        bugs.entry(span).or_insert(site);
+    }
+
+    //a written node is initialized, and the values it is a part of are initialized in part at least.
+    fn init_written(&mut self, node: usize) {
+        self.values[node].set_init(InitState::Init, None);
+        let mut child = node;
+        while self.values[child].field_id != usize::MAX {
+            let parent = self.values[child].father;
+            if self.values[parent].init != InitState::Uninit {
+                break;
+            }
+            self.values[parent].init = InitState::PartiallyInit;
+            child = parent;
+        }
+    }
+
+    //the node and the nodes it aliases, directly or through other aliases.
+    fn init_aliases(&self, node: usize) -> Vec<usize> {
+        let mut record = FxHashSet::default();
+        let mut aliases = Vec::new();
+        let mut stack = vec![node];
+        while let Some(cur) = stack.pop() {
+            if record.insert(cur) {
+                aliases.push(cur);
+                stack.extend(self.values[cur].alias.iter().copied());
+            }
+        }
+        aliases
+    }
+
+    fn find_uninit(&self, node: usize) -> Option<usize> {
+        self.init_aliases(node)
+            .into_iter()
+            .find(|alias| self.values[*alias].init == InitState::Uninit)
+    }
+
+    fn place_ty(&self, place: &Place<'tcx>) -> Ty<'tcx> {
+        let mut place_ty = PlaceTy::from_ty(self.local_tys[place.local.as_usize()]);
+        for elem in place.projection {
+            place_ty = place_ty.projection_ty(self.tcx, elem);
+        }
+        place_ty.ty
+    }
+}
@@ -1,4 +1,5 @@
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::mir::Operand;
+use rustc_middle::ty::{ParamEnv, TyCtxt};
 use rustc_span::symbol::Symbol;
 use rustc_span::def_id::DefId;
 use rustc_span::{FileName, FileNameDisplayPreference};
@@ -42,3 +43,14 @@ fn convert_filename(filename: FileName) -> String {
         _ => "<unknown>".to_string(),
     }
 }
+
+// whether the last argument of a call that takes a count, e.g., `ptr::copy` or `Vec::set_len`, is a constant zero.
+pub fn is_zero_count<'tcx>(tcx: TyCtxt<'tcx>, param_env: ParamEnv<'tcx>, args: &[Operand<'tcx>]) -> bool {
+    if args.len() < 2 {
+        return false;
+    }
+    match args.last() {
+        Some(Operand::Constant(count)) => count.const_.try_eval_target_usize(tcx, param_env) == Some(0),
+        _ => false,
+    }
+}
@@ -0,0 +1,91 @@
+//! RAP's check of `unsafe impl Send` and `unsafe impl Sync` on generic types (`-Z send-sync`).
+//! An ADT that owns a `T` may only be sent to another thread if `T: Send`, and an ADT whose
+//! `&self` API gives out `&T` may only be shared between threads if `T: Sync`; an unsafe impl
+//! that does not require the bounds its fields and API need is reported.
+
+pub mod usage;
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_middle::ty::{self, GenericParamDefKind, TyCtxt};
+use rustc_session::lint::builtin::UNSOUND_SEND_SYNC;
+use rustc_span::def_id::{DefId, LocalDefId};
+use rustc_span::symbol::sym;
+use rustc_trait_selection::infer::TyCtxtInferExt;
+use rustc_trait_selection::traits::type_known_to_meet_bound_modulo_regions;
+
+use crate::errors;
+use usage::{param_uses, ParamUse};
+
+/// Checks every `Send` and `Sync` impl of the crate.
+pub fn check_send_sync(tcx: TyCtxt<'_>) {
+    let (Some(send), Some(sync)) =
+        (tcx.get_diagnostic_item(sym::Send), tcx.lang_items().sync_trait())
+    else {
+        return;
+    };
+    for (trait_id, for_send) in [(send, true), (sync, false)] {
+        let Some(impls) = tcx.all_local_trait_impls(()).get(&trait_id) else {
+            continue;
+        };
+        for &impl_id in impls {
+            check_impl(tcx, impl_id, trait_id, for_send, send, sync);
+        }
+    }
+}
+
+fn check_impl(
+    tcx: TyCtxt<'_>,
+    impl_id: LocalDefId,
+    trait_id: DefId,
+    for_send: bool,
+    send: DefId,
+    sync: DefId,
+) {
+    if tcx.impl_polarity(impl_id) != ty::ImplPolarity::Positive {
+        return;
+    }
+    let Some(trait_ref) = tcx.impl_trait_ref(impl_id) else {
+        return;
+    };
+    let self_ty = trait_ref.instantiate_identity().self_ty();
+    let ty::Adt(adt, args) = *self_ty.kind() else {
+        return;
+    };
+    let param_env = tcx.param_env(impl_id);
+    let infcx = tcx.infer_ctxt().build();
+    for param in &tcx.generics_of(adt.did()).params {
+        if !matches!(param.kind, GenericParamDefKind::Type { .. }) {
+            continue;
+        }
+        let index = param.index as usize;
+        let ty = args.type_at(index);
+        //the first use that needs each bound the impl does not require.
+        let mut missing: FxIndexMap<DefId, ParamUse> = FxIndexMap::default();
+        for param_use in param_uses(tcx, adt, index) {
+            let Some(needs_send) = param_use.usage.needs_send(for_send) else {
+                continue;
+            };
+            let bound = if needs_send { send } else { sync };
+            if !missing.contains_key(&bound)
+                && !type_known_to_meet_bound_modulo_regions(&infcx, param_env, ty, bound)
+            {
+                missing.insert(bound, param_use);
+            }
+        }
+        for (bound, param_use) in missing {
+            tcx.emit_spanned_lint(
+                UNSOUND_SEND_SYNC,
+                tcx.local_def_id_to_hir_id(impl_id),
+                tcx.def_span(impl_id),
+                errors::SendSyncMissingBound {
+                    trait_name: tcx.item_name(trait_id),
+                    self_ty,
+                    ty,
+                    bound: tcx.item_name(bound),
+                    usage: param_use.usage.name(),
+                    use_span: param_use.span,
+                },
+            );
+        }
+    }
+}
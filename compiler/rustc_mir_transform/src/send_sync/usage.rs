@@ -0,0 +1,166 @@
+//! How a generic parameter of an ADT is used by the fields of the ADT and by the methods of
+//! its inherent impls that take `&self`, which are what other threads can reach once the ADT
+//! is `Send` or `Sync`.
+
+use rustc_data_structures::fx::FxIndexSet;
+use rustc_hir::Mutability;
+use rustc_middle::ty::{self, AdtDef, GenericArgs, Ty, TyCtxt};
+use rustc_span::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Usage {
+    /// A field owns the parameter, directly or through a raw pointer or another type.
+    FieldOwned,
+    /// A field holds `&T`.
+    FieldShared,
+    /// A field holds `&mut T`.
+    FieldMut,
+    /// A public field holds the parameter in any way.
+    FieldPub,
+    /// A `&self` method returns the parameter by value or as `&mut T`.
+    ApiOut,
+    /// A `&self` method returns `&T`.
+    ApiOutRef,
+    /// A `&self` method takes the parameter by value.
+    ApiIn,
+}
+
+impl Usage {
+    pub fn name(self) -> &'static str {
+        match self {
+            Usage::FieldOwned => "field_owned",
+            Usage::FieldShared => "field_shared",
+            Usage::FieldMut => "field_mut",
+            Usage::FieldPub => "field_pub",
+            Usage::ApiOut => "api_out",
+            Usage::ApiOutRef => "api_out_ref",
+            Usage::ApiIn => "api_in",
+        }
+    }
+
+    /// Whether the usage needs the parameter to be `Send`, rather than `Sync`, for the ADT
+    /// to be `Send` (if `for_send`) or `Sync`; `None` if it does not matter for that trait.
+    pub fn needs_send(self, for_send: bool) -> Option<bool> {
+        match (for_send, self) {
+            (true, Usage::FieldOwned | Usage::FieldMut) => Some(true),
+            (true, Usage::FieldShared) => Some(false),
+            (false, Usage::ApiOut | Usage::ApiIn) => Some(true),
+            (false, Usage::ApiOutRef | Usage::FieldPub) => Some(false),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ParamUse {
+    pub usage: Usage,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Access {
+    Owned,
+    Shared,
+    Mut,
+}
+
+//how `target` occurs in `ty`; a reference is shared if any reference on the way is.
+fn collect_accesses<'tcx>(
+    ty: Ty<'tcx>,
+    target: Ty<'tcx>,
+    access: Access,
+    found: &mut FxIndexSet<Access>,
+) {
+    if ty == target {
+        found.insert(access);
+        return;
+    }
+    match *ty.kind() {
+        ty::Ref(_, inner, mutbl) => {
+            let access = match (access, mutbl) {
+                (Access::Shared, _) | (_, Mutability::Not) => Access::Shared,
+                _ => Access::Mut,
+            };
+            collect_accesses(inner, target, access, found);
+        }
+        ty::RawPtr(ty::TypeAndMut { ty: inner, .. }) | ty::Array(inner, _) | ty::Slice(inner) => {
+            collect_accesses(inner, target, access, found);
+        }
+        ty::Adt(_, args) => {
+            for arg in args.types() {
+                collect_accesses(arg, target, access, found);
+            }
+        }
+        ty::Tuple(tys) => {
+            for ty in tys {
+                collect_accesses(ty, target, access, found);
+            }
+        }
+        //functions, closures and trait objects do not hold values of their parameter types.
+        _ => {}
+    }
+}
+
+fn accesses<'tcx>(ty: Ty<'tcx>, target: Ty<'tcx>) -> FxIndexSet<Access> {
+    let mut found = FxIndexSet::default();
+    collect_accesses(ty, target, Access::Owned, &mut found);
+    found
+}
+
+/// The uses of the type parameter of `adt` at `index`, fields first.
+pub fn param_uses<'tcx>(tcx: TyCtxt<'tcx>, adt: AdtDef<'tcx>, index: usize) -> Vec<ParamUse> {
+    let mut uses = Vec::new();
+    let target = GenericArgs::identity_for_item(tcx, adt.did()).type_at(index);
+    for field in adt.all_fields() {
+        let found = accesses(tcx.type_of(field.did).instantiate_identity(), target);
+        let span = tcx.def_span(field.did);
+        for access in &found {
+            let usage = match access {
+                Access::Owned => Usage::FieldOwned,
+                Access::Shared => Usage::FieldShared,
+                Access::Mut => Usage::FieldMut,
+            };
+            uses.push(ParamUse { usage, span });
+        }
+        if !found.is_empty() && field.vis.is_public() {
+            uses.push(ParamUse { usage: Usage::FieldPub, span });
+        }
+    }
+    for &impl_id in tcx.inherent_impls(adt.did()) {
+        let ty::Adt(_, impl_args) = tcx.type_of(impl_id).instantiate_identity().kind() else {
+            continue;
+        };
+        //an impl for some instantiations only does not use the parameter generically.
+        let target = impl_args.type_at(index);
+        if !matches!(target.kind(), ty::Param(_)) {
+            continue;
+        }
+        for item in tcx.associated_items(impl_id).in_definition_order() {
+            if item.kind != ty::AssocKind::Fn
+                || !item.fn_has_self_parameter
+                || !tcx.visibility(item.def_id).is_public()
+            {
+                continue;
+            }
+            let sig = tcx.fn_sig(item.def_id).instantiate_identity().skip_binder();
+            if !matches!(sig.inputs()[0].kind(), ty::Ref(_, _, Mutability::Not)) {
+                continue;
+            }
+            let span = tcx.def_span(item.def_id);
+            for access in accesses(sig.output(), target) {
+                let usage = match access {
+                    Access::Owned | Access::Mut => Usage::ApiOut,
+                    Access::Shared => Usage::ApiOutRef,
+                };
+                uses.push(ParamUse { usage, span });
+            }
+            if sig.inputs()[1..]
+                .iter()
+                .any(|input| accesses(*input, target).contains(&Access::Owned))
+            {
+                uses.push(ParamUse { usage: Usage::ApiIn, span });
+            }
+        }
+    }
+    uses
+}
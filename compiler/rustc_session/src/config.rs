@@ -195,6 +195,75 @@ pub struct InstrumentXRay {
     pub skip_exit: bool,
 }
 
+/// Settings for `-Z safedrop` flag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SafeDropCheckers {
+    /// `-Z safedrop=uaf`, report uses of values after they have been dropped
+    pub use_after_free: bool,
+    /// `-Z safedrop=df`, report values that are dropped more than once
+    pub double_free: bool,
+    /// `-Z safedrop=dp`, report returned or argument pointers that dangle
+    pub dangling_pointer: bool,
+    /// `-Z safedrop=leak`, report owned values that are never freed
+    pub memory_leak: bool,
+    /// `-Z safedrop=panic`, report ownership duplicated across a call that may unwind
+    pub panic_safety: bool,
+    /// `-Z safedrop=uninit`, report reads and drops of memory that is not initialized
+    pub uninit_memory: bool,
+}
+
+impl SafeDropCheckers {
+    /// All checkers enabled, as selected by a bare `-Z safedrop`.
+    pub fn all() -> Self {
+        SafeDropCheckers {
+            use_after_free: true,
+            double_free: true,
+            dangling_pointer: true,
+            memory_leak: true,
+            panic_safety: true,
+            uninit_memory: true,
+        }
+    }
+
+    /// No checkers enabled; the starting point for parsing an explicit list.
+    pub fn none() -> Self {
+        SafeDropCheckers {
+            use_after_free: false,
+            double_free: false,
+            dangling_pointer: false,
+            memory_leak: false,
+            panic_safety: false,
+            uninit_memory: false,
+        }
+    }
+}
+
+/// The engine SafeDrop uses to explore a function body, `-Z safedrop-mode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SafeDropMode {
+    /// Enumerate paths, and fall back to `Dataflow` for bodies with too many paths.
+    Auto,
+    /// Enumerate the paths of the body; precise, but exponential in the number of branches.
+    Path,
+    /// Iterate a dataflow analysis to a fixed point; linear in the size of the body.
+    Dataflow,
+}
+
+/// The MIR SafeDrop analyses, `-Z safedrop-mir-phase`. Generators are always analysed from
+/// the `Cleanup` MIR, since the other phases have them lowered to state machines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SafeDropMirPhase {
+    /// `optimized_mir`: the MIR codegen uses, after inlining and the other optimizations
+    /// enabled by the opt-level.
+    Optimized,
+    /// `mir_drops_elaborated_and_const_checked`: the MIR after drop elaboration, before
+    /// any optimization.
+    DropsElaborated,
+    /// The borrow-checked MIR lowered by the cleanup passes SafeDrop needs only, e.g., drop
+    /// elaboration; the same at every opt-level.
+    Cleanup,
+}
+
 #[derive(Clone, PartialEq, Hash, Debug)]
 pub enum LinkerPluginLto {
     LinkerPlugin(PathBuf),
@@ -3169,8 +3238,9 @@ pub(crate) mod dep_tracking {
         BranchProtection, CFGuard, CFProtection, CrateType, DebugInfo, DebugInfoCompression,
         ErrorOutputType, InstrumentCoverage, InstrumentXRay, LdImpl, LinkerPluginLto,
         LocationDetail, LtoCli, OomStrategy, OptLevel, OutFileName, OutputType, OutputTypes,
-        Passes, ResolveDocLinks, SourceFileHashAlgorithm, SplitDwarfKind, SwitchWithOptPath,
-        SymbolManglingVersion, TraitSolver, TrimmedDefPaths,
+        Passes, ResolveDocLinks, SafeDropCheckers, SafeDropMirPhase, SafeDropMode,
+        SourceFileHashAlgorithm, SplitDwarfKind, SwitchWithOptPath, SymbolManglingVersion,
+        TraitSolver, TrimmedDefPaths,
     };
     use crate::lint;
     use crate::options::WasiExecModel;
@@ -3240,6 +3310,9 @@ fn hash(
         TlsModel,
         InstrumentCoverage,
         InstrumentXRay,
+        SafeDropCheckers,
+        SafeDropMirPhase,
+        SafeDropMode,
         CrateType,
         MergeFunctions,
         PanicStrategy,
@@ -3413,3 +3486,12 @@ pub fn extension(self) -> &'static str {
         }
     }
 }
+
+/// Which format to use for `-Z safedrop-report`
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum SafeDropReportFormat {
+    /// A plain JSON array of findings
+    Json,
+    /// A SARIF 2.1.0 log, for code-scanning dashboards
+    Sarif,
+}
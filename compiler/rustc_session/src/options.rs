@@ -371,6 +371,11 @@ mod desc {
     pub const parse_opt_panic_strategy: &str = parse_panic_strategy;
     pub const parse_oom_strategy: &str = "either `panic` or `abort`";
     pub const parse_relro_level: &str = "one of: `full`, `partial`, or `off`";
+    pub const parse_safedrop: &str = "either no value or a comma separated list of checkers: `uaf`, `df`, `dp`, `leak`, `panic`, or `uninit`";
+    pub const parse_safedrop_mir_phase: &str =
+        "one of: `optimized` (default), `elaborated`, or `cleanup`";
+    pub const parse_safedrop_mode: &str = "one of: `auto` (default), `path`, or `dataflow`";
+    pub const parse_safedrop_report_format: &str = "`json` (default) or `sarif`";
     pub const parse_sanitizers: &str = "comma separated list of sanitizers: `address`, `cfi`, `hwaddress`, `kcfi`, `kernel-address`, `leak`, `memory`, `memtag`, `safestack`, `shadow-call-stack`, or `thread`";
     pub const parse_sanitizer_memory_track_origins: &str = "0, 1, or 2";
     pub const parse_cfguard: &str =
@@ -707,6 +712,65 @@ pub(crate) fn parse_sanitizers(slot: &mut SanitizerSet, v: Option<&str>) -> bool
         }
     }
 
+    pub(crate) fn parse_safedrop(slot: &mut Option<SafeDropCheckers>, v: Option<&str>) -> bool {
+        let Some(v) = v else {
+            *slot = Some(SafeDropCheckers::all());
+            return true;
+        };
+        let mut checkers = SafeDropCheckers::none();
+        for s in v.split(',') {
+            match s {
+                "uaf" => checkers.use_after_free = true,
+                "df" => checkers.double_free = true,
+                "dp" => checkers.dangling_pointer = true,
+                "leak" => checkers.memory_leak = true,
+                "panic" => checkers.panic_safety = true,
+                "uninit" => checkers.uninit_memory = true,
+                _ => return false,
+            }
+        }
+        *slot = Some(checkers);
+        true
+    }
+
+    pub(crate) fn parse_safedrop_mir_phase(slot: &mut SafeDropMirPhase, v: Option<&str>) -> bool {
+        *slot = match v {
+            Some("optimized") => SafeDropMirPhase::Optimized,
+            Some("elaborated") => SafeDropMirPhase::DropsElaborated,
+            Some("cleanup") => SafeDropMirPhase::Cleanup,
+            _ => return false,
+        };
+        true
+    }
+
+    pub(crate) fn parse_safedrop_mode(slot: &mut SafeDropMode, v: Option<&str>) -> bool {
+        *slot = match v {
+            Some("auto") => SafeDropMode::Auto,
+            Some("path") => SafeDropMode::Path,
+            Some("dataflow") => SafeDropMode::Dataflow,
+            _ => return false,
+        };
+        true
+    }
+
+    pub(crate) fn parse_safedrop_report_format(
+        slot: &mut SafeDropReportFormat,
+        v: Option<&str>,
+    ) -> bool {
+        match v {
+            None => true,
+            Some("json") => {
+                *slot = SafeDropReportFormat::Json;
+                true
+            }
+            Some("sarif") => {
+                *slot = SafeDropReportFormat::Sarif;
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
     pub(crate) fn parse_sanitizer_memory_track_origins(slot: &mut usize, v: Option<&str>) -> bool {
         match v {
             Some("2") | None => {
@@ -1725,6 +1789,40 @@ pub(crate) fn parse_dump_solver_proof_tree(
 written to standard error output)"),
     report_delayed_bugs: bool = (false, parse_bool, [TRACKED],
         "immediately print bugs registered with `delay_span_bug` (default: no)"),
+    safedrop: Option<SafeDropCheckers> = (None, parse_safedrop, [TRACKED],
+        "run the SafeDrop use-after-free, double-free, dangling-pointer, memory-leak, \
+        panic-safety and uninitialized-memory checks on every body in the crate; skipped for crates built with `-Z force-unstable-if-unmarked`
+        Optional checker list (default: all):
+        `=uaf`
+        `=df`
+        `=dp`
+        `=leak`
+        `=panic`
+        `=uninit`"),
+    safedrop_instances: bool = (false, parse_bool, [TRACKED],
+        "run SafeDrop on every instance of the local functions that code generation uses, with \
+        their type parameters substituted, and report the findings of all instances once at the \
+        generic function; functions without instances, and all functions in builds that do not \
+        generate code, are analysed generically (default: no)"),
+    safedrop_loop_bound: usize = (2, parse_number, [TRACKED],
+        "how many times SafeDrop runs the body of a loop when enumerating paths; it stops \
+        earlier once an iteration changes nothing (default: 2)"),
+    safedrop_mir_phase: SafeDropMirPhase = (SafeDropMirPhase::Optimized, parse_safedrop_mir_phase, [TRACKED],
+        "the MIR SafeDrop analyses: the optimized MIR (`optimized`, default), the MIR after drop \
+        elaboration and before optimizations (`elaborated`), or the borrow-checked MIR with only \
+        the cleanup passes SafeDrop needs (`cleanup`), which is the same at every opt-level"),
+    safedrop_mode: SafeDropMode = (SafeDropMode::Auto, parse_safedrop_mode, [TRACKED],
+        "the engine SafeDrop uses to explore function bodies: enumerate their paths (`path`), \
+        iterate a dataflow analysis to a fixed point (`dataflow`), or enumerate paths and fall \
+        back to dataflow for bodies with too many of them (`auto`, default)"),
+    safedrop_report: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write every SafeDrop finding of the crate to the given file"),
+    safedrop_report_format: SafeDropReportFormat = (SafeDropReportFormat::Json, parse_safedrop_report_format, [UNTRACKED],
+        "the format to use for -Z safedrop-report (`json` (default) or `sarif`)"),
+    safedrop_spec: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
+        "a TOML file declaring the effects of library and FFI functions for SafeDrop"),
+    safedrop_traces: bool = (false, parse_bool, [TRACKED],
+        "explain SafeDrop findings with the path to them and the assignments and calls that aliased the value (default: no)"),
     sanitizer: SanitizerSet = (SanitizerSet::empty(), parse_sanitizers, [TRACKED],
         "use a sanitizer"),
     sanitizer_cfi_canonical_jump_tables: Option<bool> = (Some(true), parse_opt_bool, [TRACKED],
@@ -1755,6 +1853,10 @@ pub(crate) fn parse_dump_solver_proof_tree(
         for example: `-Z self-profile-events=default,query-keys`
         all options: none, all, default, generic-activity, query-provider, query-cache-hit
                      query-blocked, incr-cache-load, incr-result-hashing, query-keys, function-args, args, llvm, artifact-sizes"),
+    send_sync: bool = (false, parse_bool, [TRACKED],
+        "check that `unsafe impl Send` and `unsafe impl Sync` require the bounds on type parameters \
+        that the fields and the `&self` API of the type need; skipped for crates built with \
+        `-Z force-unstable-if-unmarked` (default: no)"),
     share_generics: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "make the current crate share its generic instantiations"),
     show_span: Option<String> = (None, parse_opt_string, [TRACKED],
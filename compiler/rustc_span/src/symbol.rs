@@ -421,6 +421,10 @@
         associated_types,
         assume,
         assume_init,
+        assume_init_drop,
+        assume_init_mut,
+        assume_init_read,
+        assume_init_ref,
         async_await,
         async_closure,
         async_fn_in_trait,
@@ -618,9 +622,11 @@
         custom_test_frameworks,
         d,
         d32,
+        dangling_pointer,
         dbg_macro,
         dead_code,
         dealloc,
+        deallocator,
         debug,
         debug_assert_eq_macro,
         debug_assert_macro,
@@ -680,6 +686,8 @@
         dotdot: "..",
         dotdot_in_tuple_patterns,
         dotdoteq_in_patterns,
+        double_free,
+        downgrade,
         dreg,
         dreg_low16,
         dreg_low8,
@@ -800,6 +808,7 @@
         from_iter,
         from_iter_fn,
         from_output,
+        from_raw,
         from_residual,
         from_size_align_unchecked,
         from_str_method,
@@ -826,6 +835,7 @@
         generic_const_items,
         generic_param_attrs,
         get_context,
+        get_mut_unchecked,
         global_allocator,
         global_asm,
         globs,
@@ -890,6 +900,7 @@
         integral,
         into_future,
         into_iter,
+        into_raw,
         intra_doc_pointers,
         intrinsics,
         intrinsics_unaligned_volatile_load,
@@ -922,6 +933,7 @@
         lazy_normalization_consts,
         lazy_type_alias,
         le,
+        leak,
         len,
         let_chains,
         let_else,
@@ -996,6 +1008,7 @@
         mem_zeroed,
         member_constraints,
         memory,
+        memory_leak,
         memtag,
         message,
         meta,
@@ -1144,6 +1157,7 @@
         panic_misaligned_pointer_dereference,
         panic_nounwind,
         panic_runtime,
+        panic_safety,
         panic_str,
         panic_unwind,
         panicking,
@@ -1246,6 +1260,7 @@
         question_mark,
         quote,
         range_inclusive_new,
+        rap,
         raw_dylib,
         raw_eq,
         raw_identifiers,
@@ -1423,6 +1438,7 @@
         rvalue_static_promotion,
         rwpi,
         s,
+        safedrop,
         safety,
         sanitize,
         sanitizer_cfi_generalize_pointers,
@@ -1434,6 +1450,7 @@
         self_in_typedefs,
         self_struct_ctor,
         semitransparent,
+        set_len,
         shadow_call_stack,
         shl,
         shl_assign,
@@ -1579,6 +1596,7 @@
         sym,
         sync,
         t32,
+        take,
         target,
         target_abi,
         target_arch,
@@ -1633,6 +1651,7 @@
         trivial_bounds,
         truncf32,
         truncf64,
+        trusted,
         try_blocks,
         try_capture,
         try_from,
@@ -1675,6 +1694,7 @@
         underscore_lifetimes,
         uniform_paths,
         unimplemented_macro,
+        uninit_memory,
         unit,
         universal_impl_trait,
         unix,
@@ -1712,6 +1732,8 @@
         unwind_safe_trait,
         unwrap,
         unwrap_or,
+        upgrade,
+        use_after_free,
         use_extern_macros,
         use_nested_groups,
         used,
@@ -1753,6 +1775,7 @@
         width,
         windows,
         windows_subsystem,
+        with_capacity,
         with_negative_coherence,
         wrapping_add,
         wrapping_div,
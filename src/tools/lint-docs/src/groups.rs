@@ -15,6 +15,10 @@
     ("future-incompatible", "Lints that detect code that has future-compatibility problems"),
     ("rust-2018-compatibility", "Lints used to transition code from the 2015 edition to 2018"),
     ("rust-2021-compatibility", "Lints used to transition code from the 2018 edition to 2021"),
+    (
+        "safedrop::all",
+        "Memory-safety bugs found by SafeDrop, which are only checked when the crate is compiled with `-Z safedrop`",
+    ),
 ];
 
 type LintGroups = BTreeMap<String, BTreeSet<String>>;
@@ -0,0 +1,12 @@
+// compile-flags: -Z safedrop=df
+// Checks the text dump of the SafeDrop graph: the blocks with their assignments, and the
+// values with their aliases and liveness at the end of the analysis.
+
+// EMIT_MIR safedrop_dump.moved.SafeDrop.after.mir
+pub fn moved(b: Box<i32>) -> Box<i32> {
+    b
+}
+
+fn main() {
+    moved(Box::new(1));
+}
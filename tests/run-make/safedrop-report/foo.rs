@@ -0,0 +1,8 @@
+fn main() {
+    let mut x = 1;
+    let p = &mut x as *mut i32;
+    {
+        let _a = unsafe { Box::from_raw(p) };
+    }
+    let _b = unsafe { Box::from_raw(p) };
+}
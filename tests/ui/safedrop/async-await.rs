@@ -0,0 +1,23 @@
+// Checks that SafeDrop analyses async fns before the generator transform, so a
+// pointer to a local dropped before an `.await` is still tied to it afterwards.
+//
+// check-pass
+// edition:2021
+// compile-flags: -Z safedrop=uaf
+
+#![crate_type = "lib"]
+
+async fn ready() {}
+
+unsafe fn read(p: *const Vec<i32>) -> usize {
+    (*p).len()
+}
+
+pub async fn stale() -> usize {
+    let v = vec![1];
+    let p = &v as *const Vec<i32>;
+    drop(v);
+    ready().await;
+    unsafe { read(p) }
+    //~^ WARN use of a value after it has been dropped
+}
@@ -0,0 +1,21 @@
+// compile-flags: -Z safedrop
+
+pub struct Wrapper {
+    pub inner: Box<i32>,
+}
+
+pub fn wrap(inner: Box<i32>) -> Wrapper {
+    Wrapper { inner }
+}
+
+pub fn inner(wrapper: &Wrapper) -> &Box<i32> {
+    &wrapper.inner
+}
+
+pub fn pick<T>(a: T, _b: T) -> T {
+    a
+}
+
+pub fn second(_first: *mut i32, second: *mut i32) -> *mut i32 {
+    second
+}
@@ -0,0 +1,26 @@
+// Checks that only the checkers listed in `-Z safedrop` report: the leak is
+// reported, while the double free and the read of uninitialized memory are not.
+//
+// check-pass
+// compile-flags: -Z safedrop=leak
+
+#![crate_type = "lib"]
+
+use std::mem::MaybeUninit;
+
+pub fn leak() {
+    let b = Box::new(String::new());
+    let _p = Box::into_raw(b);
+    //~^ WARN value is never freed
+}
+
+pub unsafe fn drop_twice(p: *mut String) {
+    let s = std::ptr::read(p);
+    drop(s);
+    std::ptr::drop_in_place(p);
+}
+
+pub fn uninit() -> String {
+    let s = MaybeUninit::<String>::uninit();
+    unsafe { s.assume_init() }
+}
@@ -0,0 +1,28 @@
+// Checks that SafeDrop summaries of an upstream crate built with `-Z safedrop` are
+// stored in its metadata and can be used when analysing calls into it. Without the
+// summary of `second`, its result would not be known to alias its second argument.
+//
+// check-pass
+// aux-build:safedrop-summaries.rs
+// compile-flags: -Z safedrop=uaf
+
+extern crate safedrop_summaries;
+
+use safedrop_summaries::{inner, pick, second, wrap};
+
+fn main() {
+    let wrapper = wrap(Box::new(1));
+    let value = **inner(&wrapper);
+    let _boxed = pick(Box::new(value), Box::new(2));
+
+    let mut x = 1;
+    let mut y = 2;
+    let a = &mut x as *mut i32;
+    let b = &mut y as *mut i32;
+    let p = second(a, b);
+    {
+        let _b = unsafe { Box::from_raw(b) };
+    }
+    let _p = unsafe { Box::from_raw(p) };
+    //~^ WARN use of a value after it has been dropped
+}
@@ -0,0 +1,16 @@
+// Checks that the dataflow engine of SafeDrop keeps the aliases of the pointee of
+// a pointer at a control-flow merge where only one branch writes through it.
+//
+// check-pass
+// compile-flags: -Z safedrop=df -Z safedrop-mode=dataflow
+
+#![crate_type = "lib"]
+
+pub unsafe fn store_and_free(slot: *mut *mut i32, p: *mut i32, cond: bool) {
+    if cond {
+        *slot = p;
+    }
+    drop(Box::from_raw(p));
+    drop(Box::from_raw(*slot));
+    //~^ WARN value dropped more than once
+}
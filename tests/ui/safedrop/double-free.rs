@@ -0,0 +1,15 @@
+// Checks that SafeDrop reports two boxes built from the same raw pointer as a
+// `safedrop::double_free` lint pointing at both drops.
+//
+// check-pass
+// compile-flags: -Z safedrop=df
+
+fn main() {
+    let mut x = 1;
+    let p = &mut x as *mut i32;
+    {
+        let _a = unsafe { Box::from_raw(p) };
+    }
+    let _b = unsafe { Box::from_raw(p) };
+}
+//~^ WARN value dropped more than once
@@ -0,0 +1,13 @@
+// Checks that calls to `std::ptr::drop_in_place` are recognised in the real
+// sysroot, so a value dropped in place and again at the end of its scope is
+// reported.
+//
+// check-pass
+// compile-flags: -Z safedrop=df
+
+fn main() {
+    let mut b = Box::new(1);
+    let p = &mut b as *mut Box<i32>;
+    unsafe { std::ptr::drop_in_place(p) };
+}
+//~^ WARN value dropped more than once
@@ -0,0 +1,30 @@
+// Checks that SafeDrop tells apart the elements of an array moved out by a
+// pattern, and the fields of different enum variants matched in a loop.
+//
+// check-pass
+// compile-flags: -Z safedrop
+
+enum Shape {
+    Pair(Box<i32>, Box<i32>),
+    Single(Box<i32>),
+}
+
+fn consume(shapes: Vec<Shape>) {
+    for shape in shapes {
+        match shape {
+            Shape::Pair(a, b) => {
+                drop(a);
+                drop(b);
+            }
+            Shape::Single(c) => drop(c),
+        }
+    }
+}
+
+fn main() {
+    let pair = [Box::new(1), Box::new(2)];
+    let [first, second] = pair;
+    drop(first);
+    drop(second);
+    consume(vec![Shape::Single(Box::new(3)), Shape::Pair(Box::new(4), Box::new(5))]);
+}
@@ -0,0 +1,21 @@
+// Checks that the path-sensitive engine of SafeDrop rules out a double free on
+// paths that contradict a discriminant it knows, while the dataflow engine
+// joins the discriminants of both branches and reports it.
+//
+// check-pass
+// revisions: path dataflow
+//[path] compile-flags: -Z safedrop=df -Z safedrop-mode=path
+//[dataflow] compile-flags: -Z safedrop=df -Z safedrop-mode=dataflow
+
+fn main() {
+    let mut x = 1;
+    let p = &mut x as *mut i32;
+    let opt = if std::env::args().count() > 1 { Some(1) } else { None };
+    if let Some(_) = opt {
+        drop(unsafe { Box::from_raw(p) });
+    }
+    if let None = opt {
+        drop(unsafe { Box::from_raw(p) });
+        //[dataflow]~^ WARN value dropped more than once
+    }
+}
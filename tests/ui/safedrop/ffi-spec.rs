@@ -0,0 +1,20 @@
+// Checks that the effects declared in a `-Z safedrop-spec` file are used for calls to
+// foreign functions, so a buffer used after the C library frees it is reported.
+//
+// check-pass
+// compile-flags: -Z safedrop=uaf -Z safedrop-spec={{src-base}}/safedrop/auxiliary/ffi-spec.toml
+
+#![crate_type = "lib"]
+
+extern "C" {
+    fn rap_buffer_new() -> *mut u8;
+    fn rap_buffer_free(buffer: *mut u8);
+    fn rap_buffer_len(buffer: *mut u8) -> usize;
+}
+
+pub unsafe fn use_after_free() -> usize {
+    let buffer = rap_buffer_new();
+    rap_buffer_free(buffer);
+    rap_buffer_len(buffer)
+    //~^ WARN use of a value after it has been dropped
+}
@@ -0,0 +1,34 @@
+// Checks that SafeDrop does not report bugs on paths ruled out by an enum
+// discriminant known on the current path, inside loops and through moves of
+// the enum.
+//
+// check-pass
+// compile-flags: -Z safedrop
+
+fn in_loop() {
+    let mut x = 1;
+    let p = &mut x as *mut i32;
+    for _ in 0..2 {
+        let opt: Option<Box<i32>> = None;
+        if let Some(b) = opt {
+            drop(b);
+            drop(unsafe { Box::from_raw(p) });
+        }
+    }
+}
+
+fn moved() {
+    let mut x = 1;
+    let p = &mut x as *mut i32;
+    let first = unsafe { Box::from_raw(p) };
+    let opt = Some(first);
+    let moved = opt;
+    if let None = moved {
+        drop(unsafe { Box::from_raw(p) });
+    }
+}
+
+fn main() {
+    in_loop();
+    moved();
+}
@@ -0,0 +1,53 @@
+// Checks that `-Z safedrop-instances` tells apart the instances of a generic function whose
+// type parameter needs drop in some of them only: a bug found in several instances is
+// reported once, at the generic function, instances without the bug do not report it, and
+// generic functions without instances in the crate are still analysed generically.
+//
+// build-pass
+// compile-flags: -Z safedrop=panic -Z safedrop-instances
+
+#![crate_type = "lib"]
+
+// The duplicate made by `ptr::read` needs to be written back before `f` is called only if
+// the element needs drop, which is the case for the `String` and `Vec<u8>` instances but not
+// the `u8` one.
+fn map_first<T>(v: &mut Vec<T>, f: fn(T) -> T) {
+    let p = v.as_mut_ptr();
+    unsafe {
+        let x = std::ptr::read(p);
+        let y = f(x);
+        //~^^ WARN value may be dropped twice if a call panics
+        std::ptr::write(p, y);
+    }
+}
+
+pub fn map_all(v: &mut Vec<u8>, s: &mut Vec<String>, w: &mut Vec<Vec<u8>>) {
+    map_first(v, |x| x);
+    map_first(s, |x| x);
+    map_first(w, |x| x);
+}
+
+// The same function, but only instantiated with `u8`.
+fn map_first_copy<T>(v: &mut Vec<T>, f: fn(T) -> T) {
+    let p = v.as_mut_ptr();
+    unsafe {
+        let x = std::ptr::read(p);
+        let y = f(x);
+        std::ptr::write(p, y);
+    }
+}
+
+pub fn map_copy(v: &mut Vec<u8>) {
+    map_first_copy(v, |x| x);
+}
+
+// The same function without instances, where `T` may need drop.
+pub fn map_first_generic<T>(v: &mut Vec<T>, f: fn(T) -> T) {
+    let p = v.as_mut_ptr();
+    unsafe {
+        let x = std::ptr::read(p);
+        let y = f(x);
+        //~^^ WARN value may be dropped twice if a call panics
+        std::ptr::write(p, y);
+    }
+}
@@ -0,0 +1,6 @@
+// Checks that `-Z safedrop` rejects unknown checker names.
+//
+// compile-flags: -Z safedrop=uaf,race
+// error-pattern: incorrect value `uaf,race` for unstable option `safedrop`
+
+fn main() {}
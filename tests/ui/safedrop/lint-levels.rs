@@ -0,0 +1,35 @@
+// Checks that SafeDrop findings follow the usual lint level attributes: a level
+// set on the crate, one set on an item, and one set for the `safedrop::all` group.
+//
+// compile-flags: -Z safedrop
+
+#![crate_type = "lib"]
+#![deny(safedrop::memory_leak)]
+
+use std::mem::MaybeUninit;
+
+pub fn leak() {
+    let b = Box::new(String::new());
+    let _p = Box::into_raw(b);
+    //~^ ERROR value is never freed
+}
+
+#[allow(safedrop::memory_leak)]
+pub fn allowed_leak() {
+    let b = Box::new(String::new());
+    let _p = Box::into_raw(b);
+}
+
+pub fn uninit() -> String {
+    let s = MaybeUninit::<String>::uninit();
+    unsafe { s.assume_init() }
+    //~^ WARN memory is read before it is initialized
+}
+
+#[allow(safedrop::all)]
+pub fn quiet() -> String {
+    let b = Box::new(String::new());
+    let _p = Box::into_raw(b);
+    let s = MaybeUninit::<String>::uninit();
+    unsafe { s.assume_init() }
+}
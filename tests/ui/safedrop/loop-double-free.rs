@@ -0,0 +1,17 @@
+// Checks that SafeDrop reports a box rebuilt from the same raw pointer in
+// every iteration of a loop as a `safedrop::double_free` lint, and that
+// `-Z safedrop-loop-bound=1` goes back to running each loop body once.
+//
+// revisions: unrolled once
+// check-pass
+// compile-flags: -Z safedrop=df
+//[once] compile-flags: -Z safedrop-loop-bound=1
+
+fn main() {
+    let mut x = 1;
+    let p = &mut x as *mut i32;
+    for _ in 0..2 {
+        let _a = unsafe { Box::from_raw(p) };
+    }
+    //[unrolled]~^ WARN value dropped more than once
+}
@@ -0,0 +1,13 @@
+// Checks that SafeDrop runs the body of a loop more than once, so that a
+// pointer used after a previous iteration dropped its pointee is reported.
+//
+// check-pass
+// compile-flags: -Z safedrop=uaf
+
+fn main() {
+    let mut x = 1;
+    let p = &mut x as *mut i32;
+    for _ in 0..2 {
+        let _a = unsafe { Box::from_raw(p) }; //~ WARN use of a value after it has been dropped
+    }
+}
@@ -0,0 +1,32 @@
+// Checks that SafeDrop still reports bugs in a body with too many paths to
+// enumerate, by falling back to the dataflow engine, which carries a drop made
+// in one of the branches through the joins after them.
+//
+// check-pass
+// compile-flags: -Z safedrop=df
+
+fn main() {
+    let mut x = 1;
+    let p = &mut x as *mut i32;
+    let args = std::env::args().count();
+    let mut n = 0u32;
+    if args > 1 { n += 1; }
+    if args > 2 { n += 1; }
+    if args > 3 { n += 1; }
+    if args > 4 { n += 1; }
+    if args > 5 { n += 1; }
+    if args > 6 { n += 1; }
+    if args > 7 { n += 1; }
+    if args > 8 { n += 1; }
+    if args > 9 { n += 1; }
+    if args > 10 { n += 1; }
+    if args > 11 { n += 1; }
+    if args > 12 { n += 1; }
+    if args > 13 { n += 1; }
+    if args > 14 { n += 1; }
+    if n > 1 {
+        drop(unsafe { Box::from_raw(p) });
+    }
+    drop(unsafe { Box::from_raw(p) });
+    //~^ WARN value dropped more than once
+}
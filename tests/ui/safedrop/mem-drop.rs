@@ -0,0 +1,15 @@
+// Checks that calls to `std::mem::drop` are recognised in the real sysroot, so
+// dropping two boxes that own the same allocation is reported.
+//
+// check-pass
+// compile-flags: -Z safedrop=df
+
+fn main() {
+    let mut x = 1;
+    let p = &mut x as *mut i32;
+    let a = unsafe { Box::from_raw(p) };
+    let b = unsafe { Box::from_raw(p) };
+    drop(a);
+    drop(b);
+    //~^ WARN value dropped more than once
+}
@@ -0,0 +1,35 @@
+// Checks that owned values whose ownership is given up are reported unless they are
+// freed, returned, or stored behind a pointer argument before the function returns.
+//
+// check-pass
+// compile-flags: -Z safedrop=leak
+
+#![crate_type = "lib"]
+
+pub fn into_raw_leak() {
+    let b = Box::new(String::new());
+    let _p = Box::into_raw(b);
+    //~^ WARN value is never freed
+}
+
+pub fn reclaimed() {
+    let b = Box::new(String::new());
+    let p = Box::into_raw(b);
+    let b = unsafe { Box::from_raw(p) };
+    drop(b);
+}
+
+pub fn returned() -> *mut String {
+    let b = Box::new(String::new());
+    Box::into_raw(b)
+}
+
+pub fn stored(out: &mut *mut String) {
+    let b = Box::new(String::new());
+    *out = Box::into_raw(b);
+}
+
+pub fn forgotten(s: String) {
+    std::mem::forget(s);
+    //~^ WARN value is never freed
+}
@@ -0,0 +1,30 @@
+// Checks the MIR phases `-Z safedrop-mir-phase` selects. The storage markers that end the
+// life of a borrowed local are removed from the `optimized` MIR without optimizations, so
+// the pointer to it is only reported from the other phases, and the `cleanup` MIR keeps
+// them at any opt-level. The pointer is returned by a `const fn`, whose MIR is summarized
+// for its caller in every phase.
+//
+// check-pass
+// revisions: optimized elaborated cleanup cleanup_opt
+//[optimized] compile-flags: -Z safedrop=uaf -Z safedrop-mir-phase=optimized
+//[elaborated] compile-flags: -Z safedrop=uaf -Z safedrop-mir-phase=elaborated
+//[cleanup] compile-flags: -Z safedrop=uaf -Z safedrop-mir-phase=cleanup
+//[cleanup_opt] compile-flags: -Z safedrop=uaf -Z safedrop-mir-phase=cleanup -C opt-level=3
+
+const fn addr(x: &i32) -> *const i32 {
+    x
+}
+
+fn read(p: *const i32) -> i32 {
+    unsafe { *p }
+}
+
+fn main() {
+    let p: *const i32;
+    {
+        let x = 1;
+        p = addr(&x);
+    }
+    read(p);
+    //[elaborated,cleanup,cleanup_opt]~^ WARN use of a value after it has been dropped
+}
@@ -0,0 +1,15 @@
+// Checks that `-Z safedrop` runs quietly over a crate with no memory-safety bugs.
+//
+// check-pass
+// compile-flags: -Z safedrop
+
+fn consume(v: Vec<u8>) -> usize {
+    v.len()
+}
+
+fn main() {
+    let v = vec![1, 2, 3];
+    let r = &v;
+    let n = r.len();
+    let _ = consume(v) + n;
+}
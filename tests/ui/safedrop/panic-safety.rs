@@ -0,0 +1,56 @@
+// Checks that ownership duplicated by `ptr::read` or `Vec::set_len` is reported if a
+// call that may panic is made before the duplicate is written back, and not once one
+// of the owners has been forgotten.
+//
+// check-pass
+// compile-flags: -Z safedrop=panic
+
+#![crate_type = "lib"]
+
+pub fn map_in_place<T, F: FnMut(T) -> T>(v: &mut Vec<T>, mut f: F) {
+    let p = v.as_mut_ptr();
+    unsafe {
+        let x = std::ptr::read(p);
+        let y = f(x);
+        //~^^ WARN value may be dropped twice if a call panics
+        std::ptr::write(p, y);
+    }
+}
+
+pub unsafe fn grow<T: Default>(v: &mut Vec<T>) {
+    let len = v.len();
+    v.set_len(len + 1);
+    let value = T::default();
+    //~^^ WARN value may be dropped twice if a call panics
+    std::ptr::write(v.as_mut_ptr().add(len), value);
+}
+
+pub unsafe fn replace_first<T>(v: &mut Vec<T>, y: T) -> T {
+    let p = v.as_mut_ptr();
+    let x = std::ptr::read(p);
+    std::ptr::write(p, y);
+    x
+}
+
+pub fn clear_then_call<T>(v: &mut Vec<T>, f: fn()) {
+    unsafe { v.set_len(0) };
+    f();
+}
+
+pub fn map_copies(v: &mut Vec<u32>, f: fn(u32) -> u32) {
+    let p = v.as_mut_ptr();
+    unsafe {
+        let x = std::ptr::read(p);
+        let y = f(x);
+        std::ptr::write(p, y);
+    }
+}
+
+pub fn forget_then_call<T>(v: &mut Vec<T>, f: fn()) {
+    let p = v.as_mut_ptr();
+    unsafe {
+        let x = std::ptr::read(p);
+        std::mem::forget(x);
+    }
+    f();
+}
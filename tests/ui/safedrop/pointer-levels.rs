@@ -0,0 +1,13 @@
+// Checks that SafeDrop keeps the levels of a pointer to a box apart, so the
+// contents of a box freed through a raw pointer to it are seen as dropped.
+//
+// check-pass
+// compile-flags: -Z safedrop=uaf
+
+#![crate_type = "lib"]
+
+pub unsafe fn contents<'a>(p: *mut Box<Vec<i32>>) -> &'a Vec<i32> {
+    std::ptr::drop_in_place(p);
+    &**p
+    //~^ WARN use of a value after it has been dropped
+}
@@ -0,0 +1,43 @@
+// Checks that the `rap` tool attributes hide the findings they allow, on functions and on
+// the methods of an impl, and that deallocators may leave their arguments dangling while
+// other functions may not.
+//
+// check-pass
+// compile-flags: -Z safedrop
+
+#![crate_type = "lib"]
+
+use std::mem::MaybeUninit;
+
+#[rap::allow(memory_leak)]
+pub fn leak() {
+    let b = Box::new(String::new());
+    let _p = Box::into_raw(b);
+}
+
+#[rap::allow(double_free, use_after_free)]
+pub fn leak_reported() {
+    let b = Box::new(String::new());
+    let _p = Box::into_raw(b);
+    //~^ WARN value is never freed
+}
+
+pub struct Pool;
+
+#[rap::trusted]
+impl Pool {
+    pub fn take(&self) -> String {
+        let s = MaybeUninit::<String>::uninit();
+        unsafe { s.assume_init() }
+    }
+}
+
+#[rap::deallocator]
+pub unsafe fn free(p: *mut String) {
+    std::ptr::drop_in_place(p);
+}
+
+pub unsafe fn clear(p: *mut String) {
+    std::ptr::drop_in_place(p);
+}
+//~^^^ WARN pointer to a dropped value escapes this function
@@ -0,0 +1,13 @@
+// Checks that SafeDrop rejects unknown `rap` attributes and checker names.
+//
+// compile-flags: -Z safedrop
+
+#![crate_type = "lib"]
+
+#[rap::allow(double_fre)]
+//~^ ERROR unknown SafeDrop checker `double_fre`
+pub fn allowed() {}
+
+#[rap::trust]
+//~^ ERROR unknown SafeDrop attribute `rap::trust`
+pub fn trusted() {}
@@ -0,0 +1,17 @@
+// Checks that SafeDrop models `Rc::into_raw` and `Rc::from_raw`, so taking
+// back the count of a raw pointer twice is reported as a double free.
+//
+// check-pass
+// compile-flags: -Z safedrop=df
+
+use std::rc::Rc;
+
+fn main() {
+    let rc = Rc::new(1);
+    let p = Rc::into_raw(rc);
+    let a = unsafe { Rc::from_raw(p) };
+    let b = unsafe { Rc::from_raw(p) };
+    drop(a);
+    drop(b);
+    //~^ WARN value dropped more than once
+}
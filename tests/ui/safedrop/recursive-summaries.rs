@@ -0,0 +1,22 @@
+// Checks that SafeDrop summarizes mutually recursive functions together instead of
+// running into a query cycle.
+//
+// check-pass
+// compile-flags: -Z safedrop
+
+fn even(value: Box<u32>, n: u32) -> Box<u32> {
+    if n == 0 { value } else { odd(value, n - 1) }
+}
+
+fn odd(value: Box<u32>, n: u32) -> Box<u32> {
+    if n == 0 { value } else { even(value, n - 1) }
+}
+
+fn countdown(value: Box<u32>, n: u32) -> Box<u32> {
+    if n == 0 { value } else { countdown(value, n - 1) }
+}
+
+fn main() {
+    let value = even(Box::new(1), 4);
+    let _value = countdown(value, 3);
+}
@@ -0,0 +1,38 @@
+// Checks that SafeDrop does not report clones of `Rc` and `Arc`, weak
+// pointers, `RefCell` guards, `ManuallyDrop` or `mem::forget` as freed twice
+// or used after being freed. A forgotten value is leaked, which is checked by
+// memory-leak.rs.
+//
+// check-pass
+// compile-flags: -Z safedrop=uaf,df,dp
+
+use std::cell::RefCell;
+use std::mem::ManuallyDrop;
+use std::rc::Rc;
+use std::sync::Arc;
+
+fn main() {
+    let a = Rc::new(Box::new(1));
+    let b = a.clone();
+    let c = Rc::clone(&a);
+    let weak = Rc::downgrade(&b);
+    drop(a);
+    drop(b);
+    assert!(weak.upgrade().is_some());
+    drop(c);
+
+    let shared = Arc::new(1);
+    let other = Arc::clone(&shared);
+    drop(shared);
+    drop(other);
+
+    let cell = RefCell::new(vec![1]);
+    let guard = cell.borrow();
+    drop(guard);
+    cell.borrow_mut().push(2);
+
+    let mut slot = ManuallyDrop::new(Box::new(1));
+    unsafe { ManuallyDrop::drop(&mut slot) };
+    let kept = Box::new(2);
+    std::mem::forget(kept);
+}
@@ -0,0 +1,17 @@
+// Checks that `FnMut::call_mut` and `Iterator::next`, which have no MIR of
+// their own, are recognised in the real sysroot and do not create aliases.
+//
+// check-pass
+// compile-flags: -Z safedrop
+
+fn main() {
+    let v = vec![Box::new(1), Box::new(2)];
+    let mut it = v.iter();
+    let first = it.next().map(|b| **b);
+    let mut count = 0;
+    let mut bump = || count += 1;
+    bump();
+    bump();
+    assert_eq!(first, Some(1));
+    assert_eq!(count, 2);
+}
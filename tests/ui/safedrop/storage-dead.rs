@@ -0,0 +1,19 @@
+// Checks that SafeDrop treats the end of the storage of a borrowed local as
+// the end of its value, so a pointer to it used afterwards is reported.
+//
+// check-pass
+// compile-flags: -Z safedrop=uaf
+
+fn read(p: *const i32) -> i32 {
+    unsafe { *p }
+}
+
+fn main() {
+    let p: *const i32;
+    {
+        let x = 1;
+        p = &x;
+    }
+    read(p);
+    //~^ WARN use of a value after it has been dropped
+}
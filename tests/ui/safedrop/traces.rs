@@ -0,0 +1,14 @@
+// Checks that `-Z safedrop-traces` explains a finding with the assignment that
+// aliased the value and the path of basic blocks that leads to it.
+//
+// check-pass
+// compile-flags: -Z safedrop=df -Z safedrop-traces
+
+#![crate_type = "lib"]
+
+pub unsafe fn twice(p: *mut String) {
+    let q = p;
+    std::ptr::drop_in_place(p);
+    std::ptr::drop_in_place(q);
+    //~^ WARN value dropped more than once
+}
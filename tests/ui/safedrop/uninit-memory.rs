@@ -0,0 +1,62 @@
+// Checks that memory which is read or dropped before it is initialized is reported,
+// and that memory which is written first is not.
+//
+// check-pass
+// compile-flags: -Z safedrop=uninit
+
+#![crate_type = "lib"]
+#![allow(deprecated, invalid_value)]
+
+use std::alloc::{alloc, dealloc, Layout};
+use std::mem::MaybeUninit;
+
+pub fn assume_uninit() -> String {
+    let s = MaybeUninit::<String>::uninit();
+    unsafe { s.assume_init() }
+    //~^ WARN memory is read before it is initialized
+}
+
+pub fn assume_written() -> String {
+    let mut s = MaybeUninit::<String>::uninit();
+    s.write(String::new());
+    unsafe { s.assume_init() }
+}
+
+pub unsafe fn read_fresh_alloc() -> u64 {
+    let layout = Layout::new::<u64>();
+    let p = alloc(layout) as *mut u64;
+    let x = *p;
+    //~^ WARN memory is read before it is initialized
+    dealloc(p as *mut u8, layout);
+    x
+}
+
+pub unsafe fn write_then_read() -> u64 {
+    let layout = Layout::new::<u64>();
+    let p = alloc(layout) as *mut u64;
+    *p = 7;
+    let x = *p;
+    dealloc(p as *mut u8, layout);
+    x
+}
+
+pub fn drop_uninitialized() {
+    let s: String = unsafe { std::mem::uninitialized() };
+    drop(s);
+    //~^ WARN memory is dropped before it is initialized
+}
+
+pub fn set_len_uninit() {
+    let mut v: Vec<String> = Vec::with_capacity(4);
+    unsafe { v.set_len(4) };
+}
+//~^ WARN memory is dropped before it is initialized
+
+pub fn set_len_written(s: String) -> Vec<String> {
+    let mut v = Vec::with_capacity(1);
+    unsafe {
+        v.as_mut_ptr().write(s);
+        v.set_len(1);
+    }
+    v
+}
@@ -0,0 +1,15 @@
+// Checks that SafeDrop reports a pointer used after the box owning its
+// pointee has been dropped as a `safedrop::use_after_free` lint.
+//
+// check-pass
+// compile-flags: -Z safedrop=uaf
+
+fn main() {
+    let mut x = 1;
+    let p = &mut x as *mut i32;
+    {
+        let _a = unsafe { Box::from_raw(p) };
+    }
+    let _b = unsafe { Box::from_raw(p) };
+    //~^ WARN use of a value after it has been dropped
+}
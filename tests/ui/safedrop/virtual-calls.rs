@@ -0,0 +1,25 @@
+// Checks that SafeDrop resolves a call through a trait object to the impls of
+// the trait, so the pointer returned by the impl is seen to alias its argument.
+//
+// check-pass
+// compile-flags: -Z safedrop=df
+
+trait Forward {
+    fn forward(&self, p: *mut Vec<i32>) -> *mut Vec<i32>;
+}
+
+struct Same;
+
+impl Forward for Same {
+    fn forward(&self, p: *mut Vec<i32>) -> *mut Vec<i32> {
+        p
+    }
+}
+
+pub fn run(f: &dyn Forward, mut v: Vec<i32>) {
+    let q = f.forward(&mut v);
+    unsafe { std::ptr::drop_in_place(q) };
+}
+//~^ WARN value dropped more than once
+
+fn main() {}
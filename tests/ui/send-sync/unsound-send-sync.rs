@@ -0,0 +1,81 @@
+// Checks that `unsafe impl Send` and `unsafe impl Sync` are reported when they do not
+// require the bounds that the fields and the `&self` API of the type need.
+//
+// check-pass
+// compile-flags: -Z send-sync
+
+#![crate_type = "lib"]
+
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
+
+pub struct Owner<T>(*mut T);
+
+unsafe impl<T> Send for Owner<T> {}
+//~^ WARN `Send` is implemented for `Owner<T>` without requiring `T: Send`
+
+pub struct BoundedOwner<T>(*mut T);
+
+unsafe impl<T: Send> Send for BoundedOwner<T> {}
+
+pub struct Borrower<'a, T>(*const &'a T);
+
+unsafe impl<'a, T> Send for Borrower<'a, T> {}
+//~^ WARN `Send` is implemented for `Borrower<'a, T>` without requiring `T: Sync`
+
+pub struct Cell<T> {
+    value: UnsafeCell<T>,
+}
+
+impl<T> Cell<T> {
+    pub fn get(&self) -> &T {
+        unsafe { &*self.value.get() }
+    }
+}
+
+unsafe impl<T: Send> Sync for Cell<T> {}
+//~^ WARN `Sync` is implemented for `Cell<T>` without requiring `T: Sync`
+
+pub struct Queue<T> {
+    items: UnsafeCell<Vec<T>>,
+}
+
+impl<T> Queue<T> {
+    pub fn push(&self, item: T) {
+        unsafe { (*self.items.get()).push(item) }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        unsafe { (*self.items.get()).pop() }
+    }
+}
+
+unsafe impl<T: Sync> Sync for Queue<T> {}
+//~^ WARN `Sync` is implemented for `Queue<T>` without requiring `T: Send`
+
+pub struct Lock<T> {
+    value: UnsafeCell<T>,
+}
+
+pub struct Guard<'a, T> {
+    value: &'a mut T,
+}
+
+impl<T> Lock<T> {
+    pub fn lock(&self) -> Guard<'_, T> {
+        Guard { value: unsafe { &mut *self.value.get() } }
+    }
+}
+
+impl<'a, T> Guard<'a, T> {
+    pub fn get(&mut self) -> &mut T {
+        self.value
+    }
+}
+
+unsafe impl<T: Send> Sync for Lock<T> {}
+
+pub struct Marker<T>(PhantomData<T>);
+
+#[allow(rap::unsound_send_sync)]
+unsafe impl<T> Send for Marker<T> {}